@@ -0,0 +1,166 @@
+use std::fmt::{Display, Formatter};
+use crate::parse::{next_part, parse_number, ParseError};
+use crate::solver::{Answer, Part, solve, Solver};
+
+/// The accepted answers for the puzzle inputs, see answers.txt.
+pub const ANSWERS: &str = include_str!("../answers.txt");
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct RecordedAnswer {
+    pub day: usize,
+    pub part: Part,
+    pub answer: String,
+}
+
+/// Parse recorded answers. Lines starting with '#' and a space are comments, every entry starts
+/// with '<day> <part>'. An entry without answer on its line takes the following lines until the
+/// next entry (used for bitmaps).
+pub fn parse_answers(text: &str) -> Result<Vec<RecordedAnswer>, ParseError> {
+    let mut answers: Vec<RecordedAnswer> = vec![];
+    let mut multi_line = false;
+
+    for line in text.lines().filter(|line| !line.is_empty() && !line.starts_with("# ")) {
+        if !line.starts_with(|c: char| c.is_ascii_digit()) {
+            match answers.last_mut() {
+                Some(last) if multi_line => {
+                    if !last.answer.is_empty() {
+                        last.answer.push('\n');
+                    }
+                    last.answer.push_str(line);
+                    continue;
+                }
+                _ => return Err(ParseError::new(line, "expected '<day> <part>'"))
+            }
+        }
+
+        let mut split = line.splitn(3, ' ');
+        let day = parse_number(next_part(&mut split, line, "a day")?)?;
+        let part = match next_part(&mut split, line, "a part")? {
+            "1" => Part::One,
+            "2" => Part::Two,
+            p => return Err(ParseError::new(p, "expected part 1 or 2"))
+        };
+        let answer = split.next().unwrap_or_default().to_string();
+        multi_line = answer.is_empty();
+
+        answers.push(RecordedAnswer { day, part, answer })
+    }
+
+    Ok(answers)
+}
+
+/// A solver result that differs from the recorded answer.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Mismatch {
+    pub day: usize,
+    pub part: Part,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let separator = match self.expected.contains('\n') || self.actual.contains('\n') {
+            true => "\n",
+            false => " "
+        };
+
+        write!(
+            f,
+            "day {} part {}: expected{separator}{}{separator}but got{separator}{}",
+            self.day,
+            self.part.number(),
+            self.expected,
+            self.actual
+        )
+    }
+}
+
+/// Run both parts of the solver against its input and compare them to the recorded answers.
+/// Parts without a recorded answer are skipped.
+pub fn check(solver: &dyn Solver, input: &str, answers: &[RecordedAnswer]) -> Result<Vec<Mismatch>, ParseError> {
+    let mut mismatches = vec![];
+
+    for recorded in answers.iter().filter(|recorded| recorded.day == solver.day()) {
+        let answer = solve(solver, recorded.part, input)?;
+        let actual = match &answer {
+            Answer::Unreadable { reason, .. } => format!("{answer}\n{reason}"),
+            _ => answer.to_string()
+        };
+
+        if actual != recorded.answer {
+            mismatches.push(Mismatch {
+                day: recorded.day,
+                part: recorded.part,
+                expected: recorded.answer.clone(),
+                actual,
+            })
+        }
+    }
+
+    Ok(mismatches)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answers::{ANSWERS, check, Mismatch, parse_answers, RecordedAnswer};
+    use crate::input_reader::{input_dirs, read_input};
+    use crate::p1::P1;
+    use crate::solver::{Part, SOLVERS};
+
+    #[test]
+    fn parse_answers_works() {
+        let text = "# comment\n1 1 24000\n1 2 CMZ\n\n10 2\n#..#\n.##.\n11 1 10605";
+
+        assert_eq!(parse_answers(text).unwrap(), vec![
+            RecordedAnswer { day: 1, part: Part::One, answer: "24000".to_string() },
+            RecordedAnswer { day: 1, part: Part::Two, answer: "CMZ".to_string() },
+            RecordedAnswer { day: 10, part: Part::Two, answer: "#..#\n.##.".to_string() },
+            RecordedAnswer { day: 11, part: Part::One, answer: "10605".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn parse_invalid_answers_fails() {
+        let text = "1 3 24000";
+        let err = parse_answers(text).unwrap_err().locate(text);
+        assert_eq!(err.to_string(), "line 1, column 3: expected part 1 or 2 '3'");
+
+        let text = "1 1 24000\nfoo";
+        let err = parse_answers(text).unwrap_err().locate(text);
+        assert_eq!(err.to_string(), "line 2, column 1: expected '<day> <part>' 'foo'");
+    }
+
+    #[test]
+    fn check_reports_mismatches() {
+        let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+        let answers = parse_answers("1 1 24000\n1 2 42\n2 1 1").unwrap();
+
+        assert_eq!(check(&P1, input, &answers).unwrap(), vec![Mismatch {
+            day: 1,
+            part: Part::Two,
+            expected: "42".to_string(),
+            actual: "45000".to_string(),
+        }]);
+    }
+
+    /// Runs every solver against its real input, which takes a while in debug builds.
+    /// Run with `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn recorded_answers_match() {
+        let answers = parse_answers(ANSWERS).unwrap();
+        let dirs = input_dirs(None);
+
+        let mismatches = SOLVERS
+            .into_iter()
+            .flat_map(|solver| {
+                let input = read_input(solver.day(), &dirs).unwrap();
+                check(solver, &input, &answers).unwrap()
+            })
+            .map(|mismatch| mismatch.to_string())
+            .collect::<Vec<_>>();
+
+        assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+    }
+}
\ No newline at end of file
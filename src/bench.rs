@@ -0,0 +1,217 @@
+use std::time::{Duration, Instant};
+use crate::parse::ParseError;
+use crate::solver::{Part, solve, Solver};
+
+/// Wall times of repeated runs of the same work.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Timing {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Timing {
+    /// Create the timing from the samples, which must not be empty.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let mid = samples.len() / 2;
+
+        let median = match samples.len() % 2 {
+            0 => (samples[mid - 1] + samples[mid]) / 2,
+            _ => samples[mid]
+        };
+
+        Timing {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Run the work the given amount of times (at least once) and return its timing together with
+/// the result of the first run.
+pub fn measure<T>(runs: usize, mut work: impl FnMut() -> T) -> (Timing, T) {
+    let mut samples = Vec::with_capacity(runs);
+    let mut result = None;
+
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        let value = work();
+        samples.push(start.elapsed());
+        result.get_or_insert(value);
+    }
+
+    (Timing::from_samples(samples), result.unwrap())
+}
+
+/// The timings of one day. Solving a part includes parsing the input again, as every part
+/// parses the input itself.
+#[derive(Debug)]
+pub struct DayBench {
+    pub day: usize,
+    pub runs: usize,
+    /// Reading the input file and normalizing its line endings.
+    pub read: Timing,
+    /// Only parsing the input, see [Solver::parse].
+    pub parse: Timing,
+    pub part1: Timing,
+    pub part2: Timing,
+}
+
+impl DayBench {
+    fn stages(&self) -> [(&'static str, Timing); 4] {
+        [("read", self.read), ("parse", self.parse), ("part1", self.part1), ("part2", self.part2)]
+    }
+}
+
+/// Benchmark parsing the input and both parts of the solver. The timing for reading the input
+/// is measured by the caller.
+pub fn bench_day(solver: &dyn Solver, input: &str, read: Timing, runs: usize) -> Result<DayBench, ParseError> {
+    let (parse, parsed) = measure(runs, || solver.parse(input));
+    parsed.map_err(|e| e.locate(input).in_day(solver.day()))?;
+    let (part1, answer) = measure(runs, || solve(solver, Part::One, input));
+    answer?;
+    let (part2, answer) = measure(runs, || solve(solver, Part::Two, input));
+    answer?;
+
+    Ok(DayBench {
+        day: solver.day(),
+        runs: runs.max(1),
+        read,
+        parse,
+        part1,
+        part2,
+    })
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+pub fn format(benches: &[DayBench], format: Format) -> String {
+    match format {
+        Format::Table => table(benches),
+        Format::Json => json(benches),
+        Format::Csv => csv(benches)
+    }
+}
+
+fn table(benches: &[DayBench]) -> String {
+    let header = format!("{:>3}  {:<5}  {:>12}  {:>12}  {:>12}", "day", "stage", "min", "median", "max");
+
+    let rows = benches
+        .iter()
+        .flat_map(|bench| bench.stages().map(|(stage, timing)| format!(
+            "{:>3}  {:<5}  {:>12}  {:>12}  {:>12}",
+            bench.day,
+            stage,
+            millis(timing.min),
+            millis(timing.median),
+            millis(timing.max)
+        )));
+
+    [header].into_iter().chain(rows).collect::<Vec<_>>().join("\n")
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.3} ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn json(benches: &[DayBench]) -> String {
+    let days = benches
+        .iter()
+        .map(|bench| {
+            let stages = bench.stages()
+                .map(|(stage, timing)| format!(
+                    "\"{stage}\":{{\"min_ns\":{},\"median_ns\":{},\"max_ns\":{}}}",
+                    timing.min.as_nanos(),
+                    timing.median.as_nanos(),
+                    timing.max.as_nanos()
+                ))
+                .join(",");
+            format!("{{\"day\":{},\"runs\":{},{stages}}}", bench.day, bench.runs)
+        })
+        .collect::<Vec<_>>();
+
+    format!("[{}]", days.join(","))
+}
+
+fn csv(benches: &[DayBench]) -> String {
+    let rows = benches
+        .iter()
+        .flat_map(|bench| bench.stages().map(|(stage, timing)| format!(
+            "{},{stage},{},{},{},{}",
+            bench.day,
+            bench.runs,
+            timing.min.as_nanos(),
+            timing.median.as_nanos(),
+            timing.max.as_nanos()
+        )));
+
+    ["day,stage,runs,min_ns,median_ns,max_ns".to_string()].into_iter().chain(rows).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::bench::{DayBench, format, Format, measure, Timing};
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|ms| Duration::from_millis(*ms)).collect()
+    }
+
+    #[test]
+    fn timing_from_samples_works() {
+        assert_eq!(Timing::from_samples(millis(&[5, 1, 3])), Timing {
+            min: Duration::from_millis(1),
+            median: Duration::from_millis(3),
+            max: Duration::from_millis(5),
+        });
+
+        assert_eq!(Timing::from_samples(millis(&[4, 1, 2, 8])).median, Duration::from_millis(3));
+    }
+
+    #[test]
+    fn measure_returns_first_result() {
+        let mut calls = 0;
+        let (_, result) = measure(3, || {
+            calls += 1;
+            calls
+        });
+
+        assert_eq!((calls, result), (3, 1));
+    }
+
+    #[test]
+    fn formats_work() {
+        let timing = Timing::from_samples(millis(&[1, 2, 3]));
+        let benches = [DayBench { day: 7, runs: 3, read: timing, parse: timing, part1: timing, part2: timing }];
+
+        assert_eq!(format(&benches, Format::Table), "\
+day  stage           min        median           max
+  7  read       1.000 ms      2.000 ms      3.000 ms
+  7  parse      1.000 ms      2.000 ms      3.000 ms
+  7  part1      1.000 ms      2.000 ms      3.000 ms
+  7  part2      1.000 ms      2.000 ms      3.000 ms");
+
+        assert_eq!(format(&benches, Format::Csv), "\
+day,stage,runs,min_ns,median_ns,max_ns
+7,read,3,1000000,2000000,3000000
+7,parse,3,1000000,2000000,3000000
+7,part1,3,1000000,2000000,3000000
+7,part2,3,1000000,2000000,3000000");
+
+        assert_eq!(
+            format(&benches, Format::Json),
+            "[{\"day\":7,\"runs\":3,\
+            \"read\":{\"min_ns\":1000000,\"median_ns\":2000000,\"max_ns\":3000000},\
+            \"parse\":{\"min_ns\":1000000,\"median_ns\":2000000,\"max_ns\":3000000},\
+            \"part1\":{\"min_ns\":1000000,\"median_ns\":2000000,\"max_ns\":3000000},\
+            \"part2\":{\"min_ns\":1000000,\"median_ns\":2000000,\"max_ns\":3000000}}]"
+        );
+    }
+}
\ No newline at end of file
@@ -0,0 +1,346 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use crate::bench::Format;
+use crate::show::ShowOptions;
+use crate::solver::Part;
+
+pub const USAGE: &str = "\
+usage:
+  aoc_2022 <day> [--part 1|2] [--input PATH|-] [--input-dir DIR] [--json]
+  aoc_2022 <from>..=<to> [--part 1|2] [--input-dir DIR] [--json]
+  aoc_2022 all [--part 1|2] [--input-dir DIR] [--json]
+  aoc_2022 check [<day>|<from>..=<to>|all] [--input-dir DIR]
+  aoc_2022 bench [<day>|<from>..=<to>|all] [--runs N] [--format table|json|csv] [--input-dir DIR]
+  aoc_2022 show <day> [key=value ...] [--input PATH|-] [--input-dir DIR]
+
+check compares the solvers against the recorded answers in answers.txt, for all days by default.
+bench times reading and parsing the input and both parts (which parse again) of every day,
+running each 5 times by default.
+show writes a visualization of a day, configured by the key=value options of that day.
+
+The input directory defaults to $AOC_INPUT_DIR, then the current directory and the crate root.";
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Command {
+    Run(RunArgs),
+    Check(CheckArgs),
+    Bench(BenchArgs),
+    Show(ShowArgs),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct RunArgs {
+    pub days: Vec<usize>,
+    pub part: Option<Part>,
+    pub input: Option<PathBuf>,
+    pub input_dir: Option<PathBuf>,
+    pub json: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct CheckArgs {
+    pub days: Vec<usize>,
+    pub input_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct BenchArgs {
+    pub days: Vec<usize>,
+    pub runs: usize,
+    pub format: Format,
+    pub input_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ShowArgs {
+    pub day: usize,
+    pub options: ShowOptions,
+    pub input: Option<PathBuf>,
+    pub input_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct CliError(String);
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Parse the command line arguments (without the program name). `num_days` is the
+/// amount of solved days, used to expand `all` and to reject unknown days.
+pub fn parse_args(args: impl IntoIterator<Item=String>, num_days: usize) -> Result<Command, CliError> {
+    let mut args = args.into_iter().peekable();
+
+    if args.next_if(|arg| arg == "check").is_some() {
+        return parse_check_args(args, num_days);
+    }
+
+    if args.next_if(|arg| arg == "bench").is_some() {
+        return parse_bench_args(args, num_days);
+    }
+
+    if args.next_if(|arg| arg == "show").is_some() {
+        return parse_show_args(args, num_days);
+    }
+
+    let mut days = None;
+    let mut part = None;
+    let mut input = None;
+    let mut input_dir = None;
+    let mut json = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" => part = Some(parse_part(args.next())?),
+            "--input" => input = Some(parse_path("--input", args.next())?),
+            "--input-dir" => input_dir = Some(parse_path("--input-dir", args.next())?),
+            "--json" => json = true,
+            s if s.starts_with("--") => return Err(CliError(format!("unknown option '{s}'"))),
+            s => match days {
+                None => days = Some(parse_days(s, num_days)?),
+                Some(_) => return Err(CliError(format!("unexpected argument '{s}'")))
+            }
+        }
+    }
+
+    let days = days.ok_or_else(|| CliError("no day given".to_string()))?;
+
+    if input.is_some() && days.len() != 1 {
+        return Err(CliError("--input can only be used with a single day".to_string()));
+    }
+
+    Ok(Command::Run(RunArgs {
+        days,
+        part,
+        input,
+        input_dir,
+        json,
+    }))
+}
+
+fn parse_check_args(mut args: impl Iterator<Item=String>, num_days: usize) -> Result<Command, CliError> {
+    let mut days = None;
+    let mut input_dir = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input-dir" => input_dir = Some(parse_path("--input-dir", args.next())?),
+            s if s.starts_with("--") => return Err(CliError(format!("unknown option '{s}' for check"))),
+            s => match days {
+                None => days = Some(parse_days(s, num_days)?),
+                Some(_) => return Err(CliError(format!("unexpected argument '{s}'")))
+            }
+        }
+    }
+
+    Ok(Command::Check(CheckArgs {
+        days: days.unwrap_or_else(|| (1..=num_days).collect()),
+        input_dir,
+    }))
+}
+
+fn parse_bench_args(mut args: impl Iterator<Item=String>, num_days: usize) -> Result<Command, CliError> {
+    let mut days = None;
+    let mut runs = 5;
+    let mut format = Format::Table;
+    let mut input_dir = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" => runs = parse_runs(args.next())?,
+            "--format" => format = parse_format(args.next())?,
+            "--input-dir" => input_dir = Some(parse_path("--input-dir", args.next())?),
+            s if s.starts_with("--") => return Err(CliError(format!("unknown option '{s}' for bench"))),
+            s => match days {
+                None => days = Some(parse_days(s, num_days)?),
+                Some(_) => return Err(CliError(format!("unexpected argument '{s}'")))
+            }
+        }
+    }
+
+    Ok(Command::Bench(BenchArgs {
+        days: days.unwrap_or_else(|| (1..=num_days).collect()),
+        runs,
+        format,
+        input_dir,
+    }))
+}
+
+fn parse_show_args(mut args: impl Iterator<Item=String>, num_days: usize) -> Result<Command, CliError> {
+    let mut day = None;
+    let mut options = vec![];
+    let mut input = None;
+    let mut input_dir = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input = Some(parse_path("--input", args.next())?),
+            "--input-dir" => input_dir = Some(parse_path("--input-dir", args.next())?),
+            s if s.starts_with("--") => return Err(CliError(format!("unknown option '{s}' for show"))),
+            s => match (s.split_once('='), day) {
+                (Some((key, value)), _) => options.push((key.to_string(), value.to_string())),
+                (None, None) => day = Some(match parse_days(s, num_days)?.as_slice() {
+                    [day] => *day,
+                    _ => return Err(CliError("show takes a single day".to_string()))
+                }),
+                (None, Some(_)) => return Err(CliError(format!("unexpected argument '{s}'")))
+            }
+        }
+    }
+
+    Ok(Command::Show(ShowArgs {
+        day: day.ok_or_else(|| CliError("no day given".to_string()))?,
+        options: ShowOptions::new(options),
+        input,
+        input_dir,
+    }))
+}
+
+fn parse_runs(arg: Option<String>) -> Result<usize, CliError> {
+    match arg.as_deref().map(str::parse::<usize>) {
+        Some(Ok(runs)) if runs > 0 => Ok(runs),
+        _ => Err(CliError("--runs requires a positive number".to_string()))
+    }
+}
+
+fn parse_format(arg: Option<String>) -> Result<Format, CliError> {
+    match arg.as_deref() {
+        Some("table") => Ok(Format::Table),
+        Some("json") => Ok(Format::Json),
+        Some("csv") => Ok(Format::Csv),
+        Some(s) => Err(CliError(format!("unknown format '{s}', expected table, json or csv"))),
+        None => Err(CliError("--format requires table, json or csv".to_string()))
+    }
+}
+
+fn parse_part(arg: Option<String>) -> Result<Part, CliError> {
+    match arg.as_deref() {
+        Some("1") => Ok(Part::One),
+        Some("2") => Ok(Part::Two),
+        Some(s) => Err(CliError(format!("unknown part '{s}', expected 1 or 2"))),
+        None => Err(CliError("--part requires 1 or 2".to_string()))
+    }
+}
+
+fn parse_path(option: &str, arg: Option<String>) -> Result<PathBuf, CliError> {
+    arg.map(PathBuf::from).ok_or_else(|| CliError(format!("{option} requires a path")))
+}
+
+fn parse_days(arg: &str, num_days: usize) -> Result<Vec<usize>, CliError> {
+    let days = if arg == "all" {
+        (1..=num_days).collect()
+    } else if let Some((from, to)) = arg.split_once("..=") {
+        (parse_day(from)?..=parse_day(to)?).collect()
+    } else if let Some((from, to)) = arg.split_once("..") {
+        (parse_day(from)?..parse_day(to)?).collect()
+    } else {
+        vec![parse_day(arg)?]
+    };
+
+    match days.iter().find(|day| **day == 0 || **day > num_days) {
+        Some(day) => Err(CliError(format!("unknown day {day}, available days are 1..={num_days}"))),
+        None if days.is_empty() => Err(CliError(format!("the range '{arg}' contains no days"))),
+        None => Ok(days)
+    }
+}
+
+fn parse_day(s: &str) -> Result<usize, CliError> {
+    s.parse::<usize>().map_err(|_| CliError(format!("'{s}' is not a day")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use crate::bench::Format;
+    use crate::cli::{BenchArgs, CheckArgs, CliError, Command, parse_args, RunArgs, ShowArgs};
+    use crate::show::ShowOptions;
+    use crate::solver::Part;
+
+    fn parse(args: &str) -> Result<Command, CliError> {
+        parse_args(args.split_whitespace().map(String::from), 17)
+    }
+
+    #[test]
+    fn parse_single_day_works() {
+        assert_eq!(parse("5 --part 2 --input foo.txt --json"), Ok(Command::Run(RunArgs {
+            days: vec![5],
+            part: Some(Part::Two),
+            input: Some(PathBuf::from("foo.txt")),
+            input_dir: None,
+            json: true,
+        })));
+    }
+
+    #[test]
+    fn parse_ranges_works() {
+        assert_eq!(parse("all"), Ok(Command::Run(RunArgs { days: (1..=17).collect(), part: None, input: None, input_dir: None, json: false })));
+        assert_eq!(parse("5..=12"), Ok(Command::Run(RunArgs { days: (5..=12).collect(), part: None, input: None, input_dir: None, json: false })));
+        assert_eq!(parse("5..7 --part 1 --input-dir inputs"), Ok(Command::Run(RunArgs {
+            days: vec![5, 6],
+            part: Some(Part::One),
+            input: None,
+            input_dir: Some(PathBuf::from("inputs")),
+            json: false,
+        })));
+    }
+
+    #[test]
+    fn parse_check_works() {
+        assert_eq!(parse("check"), Ok(Command::Check(CheckArgs { days: (1..=17).collect(), input_dir: None })));
+        assert_eq!(parse("check 3..=4 --input-dir inputs"), Ok(Command::Check(CheckArgs {
+            days: vec![3, 4],
+            input_dir: Some(PathBuf::from("inputs")),
+        })));
+    }
+
+    #[test]
+    fn parse_bench_works() {
+        assert_eq!(parse("bench"), Ok(Command::Bench(BenchArgs {
+            days: (1..=17).collect(),
+            runs: 5,
+            format: Format::Table,
+            input_dir: None,
+        })));
+        assert_eq!(parse("bench 12 --runs 3 --format csv"), Ok(Command::Bench(BenchArgs {
+            days: vec![12],
+            runs: 3,
+            format: Format::Csv,
+            input_dir: None,
+        })));
+    }
+
+    #[test]
+    fn parse_show_works() {
+        assert_eq!(parse("show 12 part=2 --input - ppm=out.ppm"), Ok(Command::Show(ShowArgs {
+            day: 12,
+            options: ShowOptions::new(vec![
+                ("part".to_string(), "2".to_string()),
+                ("ppm".to_string(), "out.ppm".to_string()),
+            ]),
+            input: Some(PathBuf::from("-")),
+            input_dir: None,
+        })));
+    }
+
+    #[test]
+    fn parse_invalid_args_fails() {
+        assert!(parse("").is_err());
+        assert!(parse("18").is_err());
+        assert!(parse("0").is_err());
+        assert!(parse("15..=18").is_err());
+        assert!(parse("12..=5").is_err());
+        assert!(parse("five").is_err());
+        assert!(parse("5 --part 3").is_err());
+        assert!(parse("5 --verbose").is_err());
+        assert!(parse("all --input foo.txt").is_err());
+        assert!(parse("5 --input-dir").is_err());
+        assert!(parse("check 5 --json").is_err());
+        assert!(parse("bench --runs 0").is_err());
+        assert!(parse("bench --format xml").is_err());
+        assert!(parse("show").is_err());
+        assert!(parse("show all").is_err());
+        assert!(parse("show 12 13").is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,295 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+use pad::Position;
+use crate::parse::ParseError;
+
+/// Offsets of the horizontal and vertical neighbours.
+pub const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets of all neighbours, including the diagonal ones.
+#[allow(dead_code)]
+pub const NEIGHBOURS_8: [(isize, isize); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+/// A rectangular 2D board stored row by row. Positions start at (0, 0) in the top left corner,
+/// x grows to the right and y grows downwards, like the lines of the puzzle input.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self where T: Clone {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, f: impl FnMut(Position) -> T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: (0..height)
+                .flat_map(|y| (0..width).map(move |x| Position::new(x as isize, y as isize)))
+                .map(f)
+                .collect(),
+        }
+    }
+
+    /// Parse every character of the input with the given mapping, which returns None for
+    /// unexpected characters. `what` describes the expected characters in the error.
+    pub fn parse(input: &str, what: &str, mut map: impl FnMut(char) -> Option<T>) -> Result<Self, ParseError> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        for line in input.lines() {
+            let mut len = 0;
+
+            for (i, c) in line.char_indices() {
+                let cell = map(c).ok_or_else(|| ParseError::new(&line[i..i + c.len_utf8()], format!("expected {what}")))?;
+                cells.push(cell);
+                len += 1;
+            }
+
+            match width {
+                None => width = Some(len),
+                Some(width) if width != len => return Err(ParseError::new(line, format!("expected {width} columns"))),
+                Some(_) => ()
+            }
+
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Grid { width, height, cells }),
+            _ => Err(ParseError::missing(input, "at least one row"))
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        pos.x >= 0 && pos.y >= 0 && (pos.x as usize) < self.width && (pos.y as usize) < self.height
+    }
+
+    fn index_of(&self, pos: Position) -> Option<usize> {
+        match self.contains(pos) {
+            true => Some(pos.y as usize * self.width + pos.x as usize),
+            false => None
+        }
+    }
+
+    pub fn get(&self, pos: Position) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Position) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Set the value at the position and return the previous one, or None if the position
+    /// is outside the grid (in which case nothing changes).
+    #[allow(dead_code)]
+    pub fn set(&mut self, pos: Position, value: T) -> Option<T> {
+        self.get_mut(pos).map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item=Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Position::new(x as isize, y as isize)))
+    }
+
+    /// All positions with their values, row by row.
+    pub fn iter(&self) -> impl Iterator<Item=(Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The first position with a value matching the predicate.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Position> {
+        self.iter().find(|(_, value)| predicate(value)).map(|(pos, _)| pos)
+    }
+
+    /// The values of the row. Panics if the row is outside the grid, like indexing does.
+    #[allow(dead_code)]
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "row {y} is outside the grid");
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item=&[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    /// The values of the column from top to bottom. Panics if the column is outside the grid,
+    /// like indexing does.
+    #[allow(dead_code)]
+    pub fn column(&self, x: usize) -> impl Iterator<Item=&T> {
+        assert!(x < self.width, "column {x} is outside the grid");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    fn offsets<'a>(&'a self, pos: Position, offsets: &'a [(isize, isize)]) -> impl Iterator<Item=Position> + 'a {
+        offsets
+            .iter()
+            .map(move |(dx, dy)| Position::new(pos.x + dx, pos.y + dy))
+            .filter(|n| self.contains(*n))
+    }
+
+    /// The horizontal and vertical neighbours inside the grid.
+    pub fn neighbours_4(&self, pos: Position) -> impl Iterator<Item=Position> + '_ {
+        self.offsets(pos, &NEIGHBOURS_4)
+    }
+
+    /// All neighbours inside the grid, including the diagonal ones.
+    #[allow(dead_code)]
+    pub fn neighbours_8(&self, pos: Position) -> impl Iterator<Item=Position> + '_ {
+        self.offsets(pos, &NEIGHBOURS_8)
+    }
+
+    /// The positions from the start (exclusive) in steps of (dx, dy) until the edge of the grid.
+    pub fn ray(&self, start: Position, (dx, dy): (isize, isize)) -> impl Iterator<Item=Position> + '_ {
+        (1..)
+            .map(move |i| Position::new(start.x + i * dx, start.y + i * dy))
+            .take_while(|pos| self.contains(*pos))
+    }
+
+    #[allow(dead_code)]
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Position) -> &Self::Output {
+        self.get(pos).unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, pos: Position) -> &mut Self::Output {
+        self.get_mut(pos).unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pad::Position;
+    use crate::grid::Grid;
+
+    const INPUT: &str = "123\n456";
+
+    fn numbers() -> Grid<u32> {
+        Grid::parse(INPUT, "a digit", |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn parse_works() {
+        let grid = numbers();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Position::new(2, 0)], 3);
+        assert_eq!(grid[Position::new(0, 1)], 4);
+        assert_eq!(grid.to_string(), INPUT);
+    }
+
+    #[test]
+    fn parse_invalid_input_fails() {
+        let input = "123\n4x6";
+        let err = Grid::parse(input, "a digit", |c| c.to_digit(10)).unwrap_err().locate(input);
+        assert_eq!(err.to_string(), "line 2, column 2: expected a digit 'x'");
+
+        let input = "123\n45";
+        let err = Grid::parse(input, "a digit", |c| c.to_digit(10)).unwrap_err().locate(input);
+        assert_eq!(err.to_string(), "line 2, column 1: expected 3 columns '45'");
+
+        assert!(Grid::parse("", "a digit", |c| c.to_digit(10)).is_err());
+    }
+
+    #[test]
+    fn get_and_set_are_bounds_checked() {
+        let mut grid = numbers();
+
+        assert_eq!(grid.get(Position::new(-1, 0)), None);
+        assert_eq!(grid.get(Position::new(3, 0)), None);
+        assert_eq!(grid.set(Position::new(0, 2), 9), None);
+        assert_eq!(grid.set(Position::new(1, 1), 9), Some(5));
+        assert_eq!(grid.to_string(), "123\n496");
+    }
+
+    #[test]
+    fn neighbours_work() {
+        let grid = numbers();
+
+        let mut neighbours = grid.neighbours_4(Position::new(0, 0)).map(|pos| grid[pos]).collect::<Vec<_>>();
+        neighbours.sort();
+        assert_eq!(neighbours, [2, 4]);
+
+        let mut neighbours = grid.neighbours_8(Position::new(1, 0)).map(|pos| grid[pos]).collect::<Vec<_>>();
+        neighbours.sort();
+        assert_eq!(neighbours, [1, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn rows_columns_and_rays_work() {
+        let grid = numbers();
+
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), [2, 5]);
+        assert_eq!(grid.ray(Position::new(2, 1), (-1, 0)).map(|pos| grid[pos]).collect::<Vec<_>>(), [5, 4]);
+        assert_eq!(grid.ray(Position::new(0, 0), (1, 1)).map(|pos| grid[pos]).collect::<Vec<_>>(), [5]);
+        assert_eq!(grid.find(|value| *value == 6), Some(Position::new(2, 1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "row 2 is outside the grid")]
+    fn row_outside_panics() {
+        numbers().row(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 is outside the grid")]
+    fn column_outside_panics() {
+        let _ = numbers().column(3);
+    }
+
+    #[test]
+    fn constructors_work() {
+        assert_eq!(Grid::new(2, 1, '.').to_string(), "..");
+        assert_eq!(Grid::from_fn(3, 2, |pos| pos.x + pos.y).to_string(), "012\n123");
+        assert_eq!(numbers().map(|n| n % 2 == 0).to_string(), "falsetruefalse\ntruefalsetrue");
+    }
+}
\ No newline at end of file
@@ -1,5 +1,142 @@
+use std::fmt::{Display, Formatter};
 use std::fs::read_to_string;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use crate::grid::Grid;
+use crate::parse::ParseError;
 
-pub fn read_input(day: usize) -> String {
-    read_to_string(format!("./p{day}_input.txt")).expect("failed to read input")
+/// Environment variable to set the directory containing the `p{day}_input.txt` files.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+#[derive(Debug)]
+pub enum InputError {
+    NotFound(Vec<PathBuf>),
+    Io(PathBuf, std::io::Error),
+    Stdin(std::io::Error),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::NotFound(tried) => {
+                let tried = tried.iter().map(|path| path.display().to_string()).collect::<Vec<_>>();
+                write!(f, "input file not found, tried {}", tried.join(", "))
+            }
+            InputError::Io(path, e) => write!(f, "failed to read {}: {e}", path.display()),
+            InputError::Stdin(e) => write!(f, "failed to read stdin: {e}")
+        }
+    }
+}
+
+/// The directories searched for the input files, in order. An explicitly given directory
+/// wins over the environment variable. Without both, the current directory and the crate
+/// root are searched, so the binary also works when not launched from the repository.
+pub fn input_dirs(dir: Option<PathBuf>) -> Vec<PathBuf> {
+    match dir.or_else(|| std::env::var_os(INPUT_DIR_VAR).map(PathBuf::from)) {
+        Some(dir) => vec![dir],
+        None => vec![PathBuf::from("."), PathBuf::from(env!("CARGO_MANIFEST_DIR"))]
+    }
+}
+
+/// Read the input of the given day from the first directory containing it.
+pub fn read_input(day: usize, dirs: &[PathBuf]) -> Result<String, InputError> {
+    let paths = dirs
+        .iter()
+        .map(|dir| dir.join(format!("p{day}_input.txt")))
+        .collect::<Vec<_>>();
+
+    for path in &paths {
+        match read_file(path) {
+            Err(InputError::NotFound(_)) => continue,
+            res => return res
+        }
+    }
+
+    Err(InputError::NotFound(paths))
+}
+
+/// Read the input from the given file, or from stdin if the path is `-`.
+pub fn read_input_file(path: &Path) -> Result<String, InputError> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input).map_err(InputError::Stdin)?;
+        return Ok(normalize(&input));
+    }
+
+    read_file(path)
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+    match read_to_string(path) {
+        Ok(input) => Ok(normalize(&input)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Err(InputError::NotFound(vec![path.to_path_buf()])),
+        Err(e) => Err(InputError::Io(path.to_path_buf(), e))
+    }
+}
+
+/// Convert the line endings to LF and remove trailing newlines, so the days don't
+/// depend on how the input file was saved.
+pub fn normalize(input: &str) -> String {
+    input.replace("\r\n", "\n").trim_end_matches('\n').to_string()
+}
+
+/// Split a normalized input into its blank-line separated groups.
+pub fn blocks(input: &str) -> impl Iterator<Item=&str> {
+    input.split("\n\n")
+}
+
+/// Read a normalized input as grid of chars. Fails if the lines differ in length.
+#[allow(dead_code)] // For the days working on plain characters, the others parse with Grid::parse
+pub fn grid(input: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse(input, "a character", Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, write};
+    use std::path::PathBuf;
+    use crate::input_reader::{blocks, grid, InputError, normalize, read_input, read_input_file};
+
+    #[test]
+    fn normalize_works() {
+        assert_eq!(normalize("1\r\n2\r\n\r\n3\r\n"), "1\n2\n\n3");
+        assert_eq!(normalize("1\n2\n\n3\n\n"), "1\n2\n\n3");
+        assert_eq!(normalize("    [D]\n[N] [C]"), "    [D]\n[N] [C]");
+    }
+
+    #[test]
+    fn blocks_works() {
+        let input = normalize("1\r\n2\r\n\r\n3\r\n");
+        assert_eq!(blocks(&input).collect::<Vec<_>>(), vec!["1\n2", "3"]);
+    }
+
+    #[test]
+    fn grid_works() {
+        let chars = grid("ab\ncd").unwrap();
+        assert_eq!(chars.rows().collect::<Vec<_>>(), vec![['a', 'b'], ['c', 'd']]);
+        assert!(grid("ab\nc").is_err());
+    }
+
+    #[test]
+    fn read_input_searches_dirs() {
+        let missing = std::env::temp_dir().join("aoc_2022_missing");
+        let dir = std::env::temp_dir().join("aoc_2022_inputs");
+        create_dir_all(&dir).unwrap();
+        write(dir.join("p42_input.txt"), "1\r\n2\r\n").unwrap();
+
+        let input = read_input(42, &[missing.clone(), dir.clone()]).unwrap();
+        assert_eq!(input, "1\n2");
+
+        match read_input(43, &[missing.clone(), dir.clone()]) {
+            Err(InputError::NotFound(tried)) => assert_eq!(tried, vec![missing.join("p43_input.txt"), dir.join("p43_input.txt")]),
+            res => panic!("expected missing input, got {:?}", res)
+        }
+    }
+
+    #[test]
+    fn read_missing_input_file_reports_path() {
+        let path = PathBuf::from("./does_not_exist.txt");
+        let err = read_input_file(&path).unwrap_err();
+        assert_eq!(err.to_string(), "input file not found, tried ./does_not_exist.txt");
+    }
 }
\ No newline at end of file
@@ -1,23 +1,16 @@
 extern crate core;
 
-use crate::p10::solve_p10;
-use crate::p11::solve_p11;
-use crate::p12::solve_p12;
-use crate::p13::solve_p13;
-use crate::p14::solve_p14;
-use crate::p15::solve_p15;
-use crate::p16::solve_p16;
-use crate::p17::solve_p17;
-use crate::p1::solve_p1;
-use crate::p2::solve_p2;
-use crate::p3::solve_p3;
-use crate::p4::solve_p4;
-use crate::p5::solve_p5;
-use crate::p6::solve_p6;
-use crate::p7::solve_p7;
-use crate::p8::solve_p8;
-use crate::p9::solve_p9;
+use std::process::ExitCode;
+use crate::answers::{ANSWERS, check, parse_answers};
+use crate::bench::{bench_day, format, measure};
+use crate::cli::{BenchArgs, CheckArgs, Command, parse_args, RunArgs, ShowArgs, USAGE};
+use crate::input_reader::{input_dirs, read_input, read_input_file};
+use crate::solver::{Answer, Part, show, solve, solver_for_day, SOLVERS};
 
+mod answers;
+mod bench;
+mod cli;
+mod grid;
 mod input_reader;
 mod p1;
 mod p2;
@@ -36,30 +29,151 @@ mod p14;
 mod p15;
 mod p16;
 mod p17;
+mod parse;
+mod search;
+mod show;
+mod solver;
 
-fn main() {
-    let day = 17;
-
-    println!("Day {day}");
-
-    match day {
-        1 => solve_p1(),
-        2 => solve_p2(),
-        3 => solve_p3(),
-        4 => solve_p4(),
-        5 => solve_p5(),
-        6 => solve_p6(),
-        7 => solve_p7(),
-        8 => solve_p8(),
-        9 => solve_p9(),
-        10 => solve_p10(),
-        11 => solve_p11(),
-        12 => solve_p12(),
-        13 => solve_p13(),
-        14 => solve_p14(),
-        15 => solve_p15(),
-        16 => solve_p16(),
-        17 => solve_p17(),
-        _ => println!("error")
+fn main() -> ExitCode {
+    match parse_args(std::env::args().skip(1), SOLVERS.len()) {
+        Ok(Command::Run(args)) => run(args),
+        Ok(Command::Check(args)) => run_check(args),
+        Ok(Command::Bench(args)) => run_bench(args),
+        Ok(Command::Show(args)) => run_show(args),
+        Err(e) => {
+            eprintln!("error: {e}");
+            eprintln!("{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(args: RunArgs) -> ExitCode {
+    let dirs = input_dirs(args.input_dir);
+
+    for day in args.days {
+        let input = match &args.input {
+            Some(path) => read_input_file(path),
+            None => read_input(day, &dirs)
+        };
+
+        let input = match input {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("error: {e}");
+                return ExitCode::FAILURE;
+            }
+        };
+
+        let solver = solver_for_day(day).expect("days are validated by the cli");
+
+        if !args.json {
+            println!("Day {day}: {}", solver.title());
+        }
+
+        let parts = Part::ALL
+            .into_iter()
+            .filter(|part| args.part.is_none() || args.part == Some(*part));
+
+        for part in parts {
+            match solve(solver, part, &input) {
+                Ok(answer) => print_answer(day, part.number(), answer, args.json),
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn run_check(args: CheckArgs) -> ExitCode {
+    let answers = parse_answers(ANSWERS).expect("answers.txt should be valid");
+    let dirs = input_dirs(args.input_dir);
+    let mut failed = false;
+
+    for day in args.days {
+        let solver = solver_for_day(day).expect("days are validated by the cli");
+
+        let result = read_input(day, &dirs)
+            .map_err(|e| e.to_string())
+            .and_then(|input| check(solver, &input, &answers).map_err(|e| e.to_string()));
+
+        match result {
+            Ok(mismatches) if mismatches.is_empty() => println!("Day {day}: ok"),
+            Ok(mismatches) => {
+                failed = true;
+                mismatches.iter().for_each(|mismatch| println!("{mismatch}"))
+            }
+            Err(e) => {
+                failed = true;
+                println!("Day {day}: error: {e}")
+            }
+        }
+    }
+
+    match failed {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS
+    }
+}
+
+fn run_bench(args: BenchArgs) -> ExitCode {
+    let dirs = input_dirs(args.input_dir);
+    let mut benches = vec![];
+
+    for day in args.days {
+        let solver = solver_for_day(day).expect("days are validated by the cli");
+        let (read_timing, input) = measure(args.runs, || read_input(day, &dirs));
+
+        let result = input
+            .map_err(|e| e.to_string())
+            .and_then(|input| bench_day(solver, &input, read_timing, args.runs).map_err(|e| e.to_string()));
+
+        match result {
+            Ok(bench) => benches.push(bench),
+            Err(e) => {
+                eprintln!("error: {e}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    println!("{}", format(&benches, args.format));
+    ExitCode::SUCCESS
+}
+
+fn run_show(args: ShowArgs) -> ExitCode {
+    let input = match &args.input {
+        Some(path) => read_input_file(path),
+        None => read_input(args.day, &input_dirs(args.input_dir))
+    };
+
+    let solver = solver_for_day(args.day).expect("days are validated by the cli");
+
+    let result = input
+        .map_err(|e| e.to_string())
+        .and_then(|input| show(solver, &input, &args.options, &mut std::io::stdout().lock()).map_err(|e| e.to_string()));
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn print_answer(day: usize, part: usize, answer: Answer, json: bool) {
+    if let Answer::Unreadable { reason, .. } = &answer {
+        eprintln!("warning: day {day} part {part}: {reason}");
+    }
+
+    match (json, &answer) {
+        (true, _) => println!("{{\"day\":{day},\"part\":{part},\"answer\":{}}}", answer.to_json()),
+        (false, Answer::Bitmap(_) | Answer::Unreadable { .. }) => println!("Solution {part}:\n{answer}"),
+        (false, _) => println!("Solution {part}: {answer}")
     }
 }
\ No newline at end of file
@@ -1,21 +1,45 @@
-use crate::input_reader::read_input;
+use crate::input_reader::blocks;
+use crate::parse::{parse_number, ParseError};
+use crate::solver::{Answer, Solver};
 
-pub fn solve_p1() {
-    let input = read_input(1);
+pub struct P1;
 
-    let mut calories = input
-        .split("\r\n\r\n")
-        .map(|part| part.split("\r\n")
-            .map(|val| val.parse::<usize>().unwrap())
-            .sum::<usize>()
-        )
-        .collect::<Vec<_>>();
+impl Solver for P1 {
+    fn day(&self) -> usize {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "Calorie Counting"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_calories(input).map(drop)
+    }
 
-    calories.sort_by(|cal_one, cal_two| cal_two.cmp(&cal_one));
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(sorted_calories(input)?[0].into())
+    }
 
-    println!("Solution 1: {}", calories[0]);
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let sum_top_three = sorted_calories(input)?.into_iter().take(3).sum::<usize>();
 
-    let sum_top_three = calories.into_iter().take(3).sum::<usize>();
+        Ok(sum_top_three.into())
+    }
+}
+
+/// The calories carried by each elf.
+fn parse_calories(input: &str) -> Result<Vec<usize>, ParseError> {
+    blocks(input)
+        .map(|block| block.lines()
+            .map(parse_number::<usize>)
+            .sum::<Result<usize, _>>()
+        )
+        .collect()
+}
 
-    println!("Solution 2: {sum_top_three}")
+fn sorted_calories(input: &str) -> Result<Vec<usize>, ParseError> {
+    let mut calories = parse_calories(input)?;
+    calories.sort_by(|cal_one, cal_two| cal_two.cmp(cal_one));
+    Ok(calories)
 }
\ No newline at end of file
@@ -1,107 +1,439 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::{Display, Formatter};
+use std::io::Write;
 use std::ops::RangeInclusive;
-use crate::input_reader::read_input;
-use crate::p10::Instruction::{Add, Noop};
+use crate::parse::{next_part, parse_number, ParseError};
+use crate::show::{ShowError, ShowOptions};
+use crate::solver::{Answer, Solver};
 
-pub fn solve_p10() {
-    let input = read_input(10);
+pub struct P10;
 
-    let sum_signal_strength = sum_signal_strength(&input);
-    println!("Solution 1: {sum_signal_strength}");
+impl Solver for P10 {
+    fn day(&self) -> usize {
+        10
+    }
+
+    fn title(&self) -> &'static str {
+        "Cathode-Ray Tube"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        Cpu::load(&InstructionSet::default(), input).map(drop)
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(sum_signal_strength(input, &CrtConfig::default())?.into())
+    }
+
+    /// The letters on the screen. If they can't be read, the answer is the screen itself
+    /// together with the unknown glyphs.
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let mut screen = Screen::new(CrtConfig::default());
+        screen.set_pixels(input)?;
+
+        match screen.read_letters() {
+            Ok(letters) => Ok(letters.into()),
+            Err(e) => Ok(Answer::Unreadable { bitmap: screen.bitmap(), reason: e.to_string() })
+        }
+    }
+
+    /// Prints the screen, configured with `width=N`, `height=N` and `sprite=N` (the sprite
+    /// width). With `format=csv` the register values during every cycle are printed instead,
+    /// with `signal` as last column: the signal strength in the sampled cycles (`start=N`
+    /// and `interval=N`).
+    fn show(&self, input: &str, options: &ShowOptions, out: &mut dyn Write) -> Result<(), ShowError> {
+        options.check_known(&["width", "height", "sprite", "start", "interval", "format"])?;
+
+        let default = CrtConfig::default();
+        let config = CrtConfig {
+            width: options.get_or("width", default.width)?,
+            height: options.get_or("height", default.height)?,
+            sprite_width: options.get_or("sprite", default.sprite_width)?,
+            sample_start: options.get_or("start", default.sample_start)?,
+            sample_interval: options.get_or("interval", default.sample_interval)?,
+        };
+
+        if config.width == 0 || config.sprite_width == 0 || config.sample_interval == 0 {
+            return Err(ShowError::Option("width, sprite and interval have to be at least 1".to_string()));
+        }
+
+        match options.get("format").unwrap_or("screen") {
+            "screen" => {
+                let mut screen = Screen::new(config);
+                screen.set_pixels(input)?;
+                writeln!(out, "{}", Answer::Bitmap(screen.bitmap()))?;
+            }
+            "csv" => {
+                let instruction_set = InstructionSet::default();
+                write_timeline(&mut Cpu::load(&instruction_set, input)?, &config, out)?;
+            }
+            format => return Err(ShowError::Option(format!("unknown format '{format}', expected screen or csv")))
+        }
+
+        Ok(())
+    }
+}
+
+/// The size of the screen, the width of the sprite and the cycles in which the signal
+/// strength is sampled.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct CrtConfig {
+    width: usize,
+    height: usize,
+    sprite_width: usize,
+    sample_start: usize,
+    sample_interval: usize,
+}
+
+impl CrtConfig {
+    /// The cycles in which the signal strength is sampled, while the screen is drawn.
+    fn samples(&self) -> impl Iterator<Item=usize> {
+        (self.sample_start..=self.width * self.height).step_by(self.sample_interval)
+    }
+}
+
+impl Default for CrtConfig {
+    /// The screen of the puzzle: 40x6 pixels, a sprite 3 pixels wide and samples in
+    /// the cycles 20, 60, 100, 140, 180 and 220.
+    fn default() -> Self {
+        CrtConfig {
+            width: 40,
+            height: 6,
+            sprite_width: 3,
+            sample_start: 20,
+            sample_interval: 40,
+        }
+    }
+}
+
+/// Write the values of all registers during every cycle as CSV, with a header row.
+fn write_timeline(cpu: &mut Cpu, config: &CrtConfig, out: &mut dyn Write) -> std::io::Result<()> {
+    let states = cpu.trace().collect::<Vec<_>>();
+    let samples = config.samples().collect::<HashSet<_>>();
+
+    let names = states
+        .iter()
+        .flat_map(|state| state.registers.values.keys().copied())
+        .collect::<BTreeSet<_>>();
+
+    let header = names.iter().map(char::to_string).collect::<Vec<_>>();
+    writeln!(out, "cycle,{},signal", header.join(","))?;
+
+    for state in states {
+        let values = names.iter().map(|name| state.registers.get(*name).to_string()).collect::<Vec<_>>();
+        let signal = match samples.contains(&state.cycle) {
+            true => (state.cycle as isize * state.registers.x()).to_string(),
+            false => String::new()
+        };
+
+        writeln!(out, "{},{},{signal}", state.cycle, values.join(","))?;
+    }
 
-    let mut screen = Screen::new();
-    screen.set_pixels(&input);
-    println!("Solution 2");
-    screen.render();
+    Ok(())
 }
 
-fn sum_signal_strength(input: &String) -> isize {
-    collect_cycles_registers(input)
-        .into_iter()
-        .filter(|(i, _)| match i {
-            20 | 60 | 100 | 140 | 180 | 220 => true,
-            _ => false
-        })
-        .map(|(i, value)| i as isize * value)
-        .sum()
+fn sum_signal_strength(input: &str, config: &CrtConfig) -> Result<isize, ParseError> {
+    let instruction_set = InstructionSet::default();
+    let mut cpu = Cpu::load(&instruction_set, input)?;
+    cpu.breakpoints.extend(config.samples());
+
+    let mut sum = 0;
+
+    while let Some(state) = cpu.run() {
+        sum += state.cycle as isize * state.registers.x();
+    }
+
+    Ok(sum)
 }
 
 struct Screen {
-    pixels: [char; 240],
+    config: CrtConfig,
+    /// The pixels row by row.
+    pixels: Vec<char>,
 }
 
 impl Screen {
-    fn new() -> Self {
+    fn new(config: CrtConfig) -> Self {
         Screen {
-            pixels: ['?'; 240]
+            config,
+            pixels: vec!['?'; config.width * config.height]
         }
     }
 
-    fn set_pixels(&mut self, input: &String) {
-        let cycles_registers = collect_cycles_registers(input);
+    fn set_pixels(&mut self, input: &str) -> Result<(), ParseError> {
+        let instruction_set = InstructionSet::default();
 
-        cycles_registers
-            .into_iter()
-            .for_each(|(cycle, register)| {
-                self.pixels[cycle - 1] = if Self::sprite_range(cycle).contains(&(register as usize)) {
+        Cpu::load(&instruction_set, input)?
+            .trace()
+            .take(self.pixels.len())
+            .for_each(|state| {
+                self.pixels[state.cycle - 1] = if self.sprite_range(state.cycle).contains(&state.registers.x()) {
                     '#'
                 } else {
                     '.'
                 }
-            })
+            });
+
+        Ok(())
+    }
+
+    /// Register values for which the sprite covers the pixel drawn in the given cycle. The
+    /// register is the middle of the sprite, or the left one of the two middle pixels.
+    fn sprite_range(&self, cycle: usize) -> RangeInclusive<isize> {
+        let position = ((cycle - 1) % self.config.width) as isize;
+        let sprite_width = self.config.sprite_width as isize;
+        position - sprite_width / 2..=position + (sprite_width - 1) / 2
     }
 
-    fn sprite_range(cycle: usize) -> RangeInclusive<usize> {
-        let position = (cycle - 1) % 40;
-        let start = position.checked_sub(1).unwrap_or(0);
-        let end = position + 1;
-        start..=end
+    fn bitmap(&self) -> Vec<Vec<bool>> {
+        self.pixels
+            .chunks(self.config.width)
+            .map(|row| row.iter().map(|pixel| *pixel == '#').collect())
+            .collect()
     }
 
-    fn render(&self) {
-        for y in 0..6 {
-            for x in 0..40 {
-                let val = self.pixels[y * 40 + x];
-                print!("{val}");
+    /// Read the capital letters drawn on the screen. Every letter is 4 pixels wide and
+    /// followed by an empty column.
+    fn read_letters(&self) -> Result<String, OcrError> {
+        let width = self.config.width;
+        let rows = self.pixels.chunks(width).collect::<Vec<_>>();
+        let mut letters = String::new();
+        let mut unknown = vec![];
+
+        for (i, x) in (0..width).step_by(GLYPH_WIDTH + 1).enumerate() {
+            let glyph = rows
+                .iter()
+                .map(|row| row[x..(x + GLYPH_WIDTH).min(width)].iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            match GLYPHS.iter().find(|(_, known)| *known == glyph) {
+                Some((letter, _)) => letters.push(*letter),
+                None => unknown.push((i, glyph))
             }
-            println!();
         }
+
+        match unknown.is_empty() {
+            true => Ok(letters),
+            false => Err(OcrError { unknown })
+        }
+    }
+}
+
+const GLYPH_WIDTH: usize = 4;
+
+/// The letters of the font used by the puzzles, drawn like the screen.
+const GLYPHS: [(char, &str); 18] = [
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', ".###\n..#.\n..#.\n..#.\n..#.\n.###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...\n#...\n.#.#\n..#.\n..#.\n..#."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// The screen shows glyphs which are not in the font.
+#[derive(Debug, Eq, PartialEq)]
+struct OcrError {
+    /// The index of every unknown glyph with its pixels.
+    unknown: Vec<(usize, String)>,
+}
+
+impl Display for OcrError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let glyphs = self.unknown
+            .iter()
+            .map(|(i, glyph)| format!("letter {}:\n{glyph}", i + 1))
+            .collect::<Vec<_>>();
+
+        write!(f, "unknown glyphs, {}", glyphs.join("\n"))
+    }
+}
+
+/// The registers of the CPU by name. X starts at 1, all others at 0.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Registers {
+    values: BTreeMap<char, isize>,
+}
+
+impl Registers {
+    fn new() -> Self {
+        Registers {
+            values: BTreeMap::from([('X', 1)])
+        }
+    }
+
+    fn get(&self, name: char) -> isize {
+        self.values.get(&name).copied().unwrap_or(0)
+    }
+
+    fn set(&mut self, name: char, value: isize) {
+        self.values.insert(name, value);
+    }
+
+    fn x(&self) -> isize {
+        self.get('X')
+    }
+}
+
+/// An instruction the CPU knows: it takes `cycles` cycles and changes the registers with
+/// its numeric arguments once they are over.
+struct Opcode {
+    name: &'static str,
+    cycles: usize,
+    arguments: usize,
+    execute: fn(&mut Registers, &[isize]),
+}
+
+/// The instructions the CPU can run, looked up by name while parsing a program.
+struct InstructionSet {
+    opcodes: Vec<Opcode>,
+}
+
+impl InstructionSet {
+    /// Add an instruction, replacing one with the same name.
+    fn with(mut self, opcode: Opcode) -> Self {
+        self.opcodes.retain(|known| known.name != opcode.name);
+        self.opcodes.push(opcode);
+        self
+    }
+
+    fn parse(&self, line: &str) -> Result<Instruction, ParseError> {
+        let mut split = line.split(' ');
+        let name = next_part(&mut split, line, "an instruction")?;
+
+        let opcode = self.opcodes
+            .iter()
+            .position(|opcode| opcode.name == name)
+            .ok_or_else(|| ParseError::new(name, "unknown instruction"))?;
+
+        let arguments = (0..self.opcodes[opcode].arguments)
+            .map(|_| parse_number(next_part(&mut split, line, "an argument")?))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(extra) = split.next() {
+            return Err(ParseError::new(extra, "unexpected argument"));
+        }
+
+        Ok(Instruction { opcode, arguments })
     }
 }
 
-fn collect_cycles_registers(input: &String) -> Vec<(usize, isize)> {
-    input
-        .lines()
-        .flat_map(instructions_from)
-        .enumerate()
-        .fold((1, vec![]), |(mut register, mut cache), (index, instruction)| {
-            cache.push((index + 1, register));
+impl Default for InstructionSet {
+    /// The instructions of the puzzle, `noop` and `addx V`.
+    fn default() -> Self {
+        InstructionSet { opcodes: vec![] }
+            .with(Opcode { name: "noop", cycles: 1, arguments: 0, execute: |_, _| () })
+            .with(Opcode { name: "addx", cycles: 2, arguments: 1, execute: |registers, args| registers.set('X', registers.x() + args[0]) })
+    }
+}
 
-            if let Add(value) = instruction {
-                register += value;
-            };
+/// A parsed instruction, the opcode is the index in the instruction set.
+struct Instruction {
+    opcode: usize,
+    arguments: Vec<isize>,
+}
 
-            (register, cache)
-        }).1
+/// The state of the CPU during a cycle, before the instruction of the cycle completes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct CycleState {
+    cycle: usize,
+    registers: Registers,
 }
 
-enum Instruction {
-    Noop,
-    Add(isize),
+struct Cpu<'a> {
+    instruction_set: &'a InstructionSet,
+    program: Vec<Instruction>,
+    registers: Registers,
+    /// The number of completed cycles.
+    cycle: usize,
+    /// The index of the running instruction.
+    pointer: usize,
+    /// The cycles the running instruction still takes, 0 if the next one has to start.
+    remaining: usize,
+    /// Cycles at which `run` stops.
+    breakpoints: HashSet<usize>,
 }
 
-fn instructions_from(s: &str) -> impl IntoIterator<Item=Instruction> {
-    let split = s.split(" ").collect::<Vec<_>>();
+impl<'a> Cpu<'a> {
+    fn new(instruction_set: &'a InstructionSet, program: Vec<Instruction>) -> Self {
+        Cpu {
+            instruction_set,
+            program,
+            registers: Registers::new(),
+            cycle: 0,
+            pointer: 0,
+            remaining: 0,
+            breakpoints: HashSet::new(),
+        }
+    }
 
-    match split[0] {
-        "noop" => vec![Noop],
-        "addx" => vec![Noop, Add(split[1].parse::<isize>().unwrap())],
-        _ => panic!("unknown")
+    /// Parse the program, one instruction per line.
+    fn load(instruction_set: &'a InstructionSet, input: &str) -> Result<Self, ParseError> {
+        let program = input
+            .lines()
+            .map(|line| instruction_set.parse(line))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Cpu::new(instruction_set, program))
+    }
+
+    /// Run one cycle and return the state during it, None if the program is over.
+    fn step(&mut self) -> Option<CycleState> {
+        let instruction = self.program.get(self.pointer)?;
+        let opcode = &self.instruction_set.opcodes[instruction.opcode];
+
+        if self.remaining == 0 {
+            self.remaining = opcode.cycles;
+        }
+
+        self.cycle += 1;
+        let state = CycleState { cycle: self.cycle, registers: self.registers.clone() };
+        self.remaining = self.remaining.saturating_sub(1);
+
+        if self.remaining == 0 {
+            (opcode.execute)(&mut self.registers, &instruction.arguments);
+            self.pointer += 1;
+        }
+
+        Some(state)
+    }
+
+    /// Run until the next breakpoint and return the state during its cycle, None if the
+    /// program ends before.
+    fn run(&mut self) -> Option<CycleState> {
+        loop {
+            let state = self.step()?;
+
+            if self.breakpoints.contains(&state.cycle) {
+                return Some(state);
+            }
+        }
+    }
+
+    /// The state during every remaining cycle.
+    fn trace(&mut self) -> impl Iterator<Item=CycleState> + use<'_, 'a> {
+        std::iter::from_fn(|| self.step())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::p10::{Screen, sum_signal_strength};
+    use crate::p10::{Cpu, CrtConfig, CycleState, InstructionSet, Opcode, P10, Registers, Screen, sum_signal_strength, write_timeline};
+    use crate::show::ShowOptions;
+    use crate::solver::{Answer, Solver};
 
     #[test]
     fn examples_work() {
@@ -250,19 +582,166 @@ addx -6
 addx -11
 noop
 noop
-noop".to_string();
+noop";
 
-        let sum_strength = sum_signal_strength(&input);
+        let sum_strength = sum_signal_strength(input, &CrtConfig::default()).unwrap();
         assert_eq!(sum_strength, 13140);
 
-        let mut screen = Screen::new();
-        screen.set_pixels(&input);
-        screen.render()
+        let mut screen = Screen::new(CrtConfig::default());
+        screen.set_pixels(input).unwrap();
+        assert_eq!(Answer::Bitmap(screen.bitmap()).to_string(), "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....")
     }
 
     #[test]
     fn screen_works() {
-        let screen = Screen::new();
-        screen.render();
+        let screen = Screen::new(CrtConfig::default());
+        assert!(screen.bitmap().iter().flatten().all(|lit| !lit));
+    }
+
+    #[test]
+    fn cpu_works() {
+        let instruction_set = InstructionSet::default();
+        let mut cpu = Cpu::load(&instruction_set, "noop\naddx 3\naddx -5").unwrap();
+
+        let xs = cpu.trace().map(|state| (state.cycle, state.registers.x())).collect::<Vec<_>>();
+        assert_eq!(xs, vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]);
+        assert_eq!(cpu.registers.x(), -1);
+        assert_eq!(cpu.step(), None);
+    }
+
+    #[test]
+    fn breakpoints_work() {
+        let instruction_set = InstructionSet::default();
+        let mut cpu = Cpu::load(&instruction_set, "noop\naddx 3\naddx -5").unwrap();
+        cpu.breakpoints.extend([2, 5, 7]);
+
+        assert_eq!(cpu.run().map(|state| state.cycle), Some(2));
+
+        let mut registers = Registers::new();
+        registers.set('X', 4);
+        assert_eq!(cpu.run(), Some(CycleState { cycle: 5, registers }));
+        assert_eq!(cpu.run(), None);
+    }
+
+    #[test]
+    fn instruction_set_works() {
+        let instruction_set = InstructionSet::default()
+            .with(Opcode { name: "mulx", cycles: 3, arguments: 1, execute: |registers, args| registers.set('X', registers.x() * args[0]) })
+            .with(Opcode { name: "sety", cycles: 1, arguments: 1, execute: |registers, args| registers.set('Y', args[0]) });
+
+        let mut cpu = Cpu::load(&instruction_set, "addx 2\nmulx 4\nsety 7").unwrap();
+
+        let xs = cpu.trace().map(|state| state.registers.x()).collect::<Vec<_>>();
+        assert_eq!(xs, vec![1, 1, 3, 3, 3, 12]);
+        assert_eq!((cpu.registers.x(), cpu.registers.get('Y')), (12, 7));
+
+        let error = |input| Cpu::load(&instruction_set, input).err().unwrap().locate(input).to_string();
+        assert_eq!(error("divx 2"), "line 1, column 1: unknown instruction 'divx'");
+        assert_eq!(error("noop\naddx"), "line 2, column 5: expected an argument");
+        assert_eq!(error("noop 1"), "line 1, column 6: unexpected argument '1'");
+    }
+
+    #[test]
+    fn read_letters_works() {
+        let mut screen = Screen::new(CrtConfig::default());
+        screen.pixels = to_pixels("\
+####..##..#....#..#.###..#....####...##.
+#....#..#.#....#..#.#..#.#....#.......#.
+###..#....#....####.###..#....###.....#.
+#....#.##.#....#..#.#..#.#....#.......#.
+#....#..#.#....#..#.#..#.#....#....#..#.
+####..###.####.#..#.###..####.#.....##..");
+
+        assert_eq!(screen.read_letters().unwrap(), "EGLHBLFJ");
+    }
+
+    #[test]
+    fn unreadable_screen_is_answered_with_the_reason() {
+        match P10.part2("noop").unwrap() {
+            Answer::Unreadable { bitmap, reason } => {
+                assert_eq!((bitmap.len(), bitmap[0].len()), (6, 40));
+                assert!(reason.starts_with("unknown glyphs, letter 1:"));
+            }
+            answer => panic!("expected an unreadable answer, got {answer:?}")
+        }
+    }
+
+    #[test]
+    fn unknown_letters_are_listed() {
+        let mut screen = Screen::new(CrtConfig::default());
+        screen.pixels = to_pixels("\
+####.#..#.####.#..#.####.#..#.####.#..#.
+#....#..#.#....#..#.#....#..#.#....#..#.
+###..####.###..####.###..####.###..####.
+#....#..#.#....#..#.#....#..#.#....#..#.
+#....#..#.#....#..#.#....#..#.#....#..#.
+##.#.#..#.#....#..#.#....#..#.###..#..#.");
+
+        let error = screen.read_letters().unwrap_err();
+        assert_eq!(error.unknown.iter().map(|(i, _)| *i).collect::<Vec<_>>(), vec![0, 6]);
+        assert_eq!(error.to_string(), "unknown glyphs, letter 1:
+####
+#...
+###.
+#...
+#...
+##.#
+letter 7:
+####
+#...
+###.
+#...
+#...
+###.");
+    }
+
+    #[test]
+    fn crt_config_works() {
+        let input = "noop\naddx 3\naddx -5\nnoop\naddx 2\nnoop";
+        let config = CrtConfig { width: 4, height: 2, sprite_width: 2, sample_start: 2, sample_interval: 3 };
+
+        assert_eq!(config.samples().collect::<Vec<_>>(), vec![2, 5, 8]);
+        // X during the cycles 2, 5 and 8 is 1, 4 and -1
+        assert_eq!(sum_signal_strength(input, &config).unwrap(), 2 + 20 - 8);
+
+        let mut screen = Screen::new(config);
+        screen.set_pixels(input).unwrap();
+        // The sprite covers X - 1 and X
+        assert_eq!(screen.pixels.iter().collect::<String>(), ".##.....");
+    }
+
+    #[test]
+    fn empty_sprite_is_rejected() {
+        let options = ShowOptions::new(vec![("sprite".to_string(), "0".to_string())]);
+        let error = P10.show("noop", &options, &mut vec![]).unwrap_err();
+        assert_eq!(error.to_string(), "width, sprite and interval have to be at least 1");
+    }
+
+    #[test]
+    fn write_timeline_works() {
+        let instruction_set = InstructionSet::default();
+        let mut cpu = Cpu::load(&instruction_set, "noop\naddx 3\naddx -5").unwrap();
+        let config = CrtConfig { sample_start: 2, sample_interval: 2, ..CrtConfig::default() };
+
+        let mut out = vec![];
+        write_timeline(&mut cpu, &config, &mut out).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "cycle,X,signal
+1,1,
+2,1,2
+3,1,
+4,4,16
+5,4,
+");
+    }
+
+    fn to_pixels(screen: &str) -> Vec<char> {
+        screen.lines().flat_map(str::chars).collect()
     }
 }
\ No newline at end of file
@@ -1,89 +1,148 @@
-use std::collections::HashMap;
-use std::ops::Mul;
-use crate::input_reader::read_input;
-use crate::p11::Operation::{Add, Square, Times};
-
-pub fn solve_p11() {
-    let input = read_input(11);
-
-    let mut runner = Runner::new(input.split("\r\n\r\n").map(|s| Monkey::from(s)));
-    runner.run();
-    let monkey_business = runner.calculate_monkey_business();
-    println!("Solution 1: {monkey_business}");
-
-    let mut runner = StressRunner::new(input.split("\r\n\r\n").map(|s| StressMonkey::from(s)));
-    runner.run();
-    let monkey_business = runner.calculate_monkey_business();
-    println!("Solution 2: {monkey_business}");
+use std::str::FromStr;
+use crate::p11::Operand::{Literal, Old};
+use crate::input_reader::blocks;
+use crate::parse::{next_part, parse_number, ParseError};
+use crate::solver::{Answer, Solver};
+
+pub struct P11;
+
+impl Solver for P11 {
+    fn day(&self) -> usize {
+        11
+    }
+
+    fn title(&self) -> &'static str {
+        "Monkey in the Middle"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_monkeys(input).map(drop)
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let monkeys = parse_monkeys(input)?;
+        let mut runner = Runner::new(monkeys, Relief::divide_by(3), 20);
+        runner.run();
+        let monkey_business = runner.calculate_monkey_business();
+        Ok(monkey_business.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let monkeys = with_modular_worry(parse_monkeys(input)?);
+        let mut runner = Runner::new(monkeys, Relief::none(), 10000);
+        runner.run();
+        let monkey_business = runner.calculate_monkey_business();
+        Ok(monkey_business.into())
+    }
 }
 
-#[derive(Debug)]
-struct Runner {
-    monkeys: Vec<Monkey>,
-    inspections: Vec<usize>,
+/// The worry level of an item, stored in a way that supports the monkeys' operations.
+trait Worry: Clone {
+    fn inspect(&self, operation: &Operation) -> Self;
+
+    fn divisible_by(&self, divisor: usize) -> bool;
 }
 
-impl Runner {
-    fn new(monkeys: impl IntoIterator<Item=Monkey>) -> Self {
-        let monkeys = monkeys.into_iter().collect::<Vec<_>>();
-        Runner {
-            inspections: vec![0; monkeys.len()],
-            monkeys,
-        }
+/// The plain worry level is signed, since operations may subtract.
+impl Worry for i128 {
+    fn inspect(&self, operation: &Operation) -> Self {
+        operation.calculate_new(*self)
     }
 
-    fn run(&mut self) {
-        for _ in 0..20 {
-            self.run_round()
+    fn divisible_by(&self, divisor: usize) -> bool {
+        self.rem_euclid(divisor as i128) == 0
+    }
+}
+
+/// A worry level kept modulo the least common multiple of all divisors, so the tests of the
+/// monkeys stay the same while the number can't grow without bounds.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Modular {
+    value: usize,
+    modulus: usize,
+}
+
+impl Worry for Modular {
+    fn inspect(&self, operation: &Operation) -> Self {
+        Modular {
+            value: operation.calculate_new_modulo(self.value, self.modulus),
+            modulus: self.modulus,
         }
     }
 
-    fn run_round(&mut self) {
-        for i in 0..self.monkeys.len() {
-            let moves = self.monkeys[i]
-                .process_items()
-                .into_iter()
-                .collect::<Vec<_>>();
+    fn divisible_by(&self, divisor: usize) -> bool {
+        self.value.is_multiple_of(divisor)
+    }
+}
 
-            self.inspections[i] += moves.len();
+/// Part 2: keep the worry levels modulo the least common multiple of the monkeys' divisors.
+fn with_modular_worry(monkeys: Vec<Monkey<i128>>) -> Vec<Monkey<Modular>> {
+    let modulus = monkeys.iter().map(|monkey| monkey.test_value).fold(1, lcm);
 
-            moves.into_iter().for_each(|mv| self.monkeys[mv.target].add_item(mv.item))
-        }
+    monkeys
+        .into_iter()
+        .map(|monkey| monkey.map_items(|value| Modular { value: value.rem_euclid(modulus as i128) as usize, modulus }))
+        .collect()
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b)
     }
+}
 
-    fn calculate_monkey_business(&self) -> u128 {
-        let mut sorted = self.inspections.clone();
-        sorted.sort();
-        sorted[sorted.len() - 1] as u128 * sorted[sorted.len() - 2] as u128
+/// What happens to the worry level after the inspection, before the item is thrown.
+struct Relief<W>(Box<dyn Fn(W) -> W>);
+
+impl<W: 'static> Relief<W> {
+    fn none() -> Self {
+        Relief(Box::new(|worry| worry))
+    }
+
+    fn custom(relief: impl Fn(W) -> W + 'static) -> Self {
+        Relief(Box::new(relief))
+    }
+}
+
+impl Relief<i128> {
+    /// Part 1: the worry level is divided by the divisor, rounded down.
+    fn divide_by(divisor: i128) -> Self {
+        Relief::custom(move |worry: i128| worry.div_euclid(divisor))
     }
 }
 
-struct StressRunner {
-    monkeys: Vec<StressMonkey>,
+struct Runner<W: Worry> {
+    monkeys: Vec<Monkey<W>>,
     inspections: Vec<usize>,
+    relief: Relief<W>,
+    rounds: usize,
 }
 
-impl StressRunner {
-    fn new(monkeys: impl IntoIterator<Item=StressMonkey>) -> Self {
+impl<W: Worry> Runner<W> {
+    fn new(monkeys: impl IntoIterator<Item=Monkey<W>>, relief: Relief<W>, rounds: usize) -> Self {
         let monkeys = monkeys.into_iter().collect::<Vec<_>>();
-        StressRunner {
+        Runner {
             inspections: vec![0; monkeys.len()],
             monkeys,
+            relief,
+            rounds,
         }
     }
 
     fn run(&mut self) {
-        for _ in 0..10000 {
+        for _ in 0..self.rounds {
             self.run_round()
         }
     }
 
     fn run_round(&mut self) {
         for i in 0..self.monkeys.len() {
-            let moves = self.monkeys[i]
-                .process_items()
-                .into_iter()
-                .collect::<Vec<_>>();
+            let moves = self.monkeys[i].process_items(&self.relief);
 
             self.inspections[i] += moves.len();
 
@@ -100,288 +159,376 @@ impl StressRunner {
 
 /// Reject humanity. Return to
 #[derive(Debug)]
-struct Monkey {
-    items: Vec<usize>,
+struct Monkey<W> {
+    items: Vec<W>,
     operation: Operation,
     test_value: usize,
     true_target: usize,
     false_target: usize,
 }
 
-impl Monkey {
-    fn process_items<'a>(&'a mut self) -> impl IntoIterator<Item=Move> + 'a {
+impl<W: Worry> Monkey<W> {
+    fn process_items(&mut self, relief: &Relief<W>) -> Vec<Move<W>> {
         let items = self.items.drain(..).collect::<Vec<_>>();
-        items.into_iter().map(|item| self.process_item(item))
+        items.into_iter().map(|item| self.process_item(item, relief)).collect()
     }
 
-    fn process_item(&self, mut item: usize) -> Move {
-        self.inspect_current(&mut item);
-        self.get_bored(&mut item);
+    fn process_item(&self, item: W, relief: &Relief<W>) -> Move<W> {
+        let item = (relief.0)(item.inspect(&self.operation));
 
-        match self.check(item) {
+        match item.divisible_by(self.test_value) {
             true => Move::new(item, self.true_target),
             false => Move::new(item, self.false_target),
         }
     }
 
-    fn inspect_current(&self, item: &mut usize) {
-        *item = self.operation.calculate_new(*item)
+    fn add_item(&mut self, item: W) {
+        self.items.push(item)
     }
 
-    fn get_bored(&self, item: &mut usize) {
-        *item = *item / 3;
+    fn map_items<V>(self, worry: impl Fn(W) -> V) -> Monkey<V> {
+        Monkey {
+            items: self.items.into_iter().map(worry).collect(),
+            operation: self.operation,
+            test_value: self.test_value,
+            true_target: self.true_target,
+            false_target: self.false_target,
+        }
     }
+}
 
-    fn check(&self, item: usize) -> bool {
-        item % self.test_value == 0
+struct Move<W> {
+    item: W,
+    target: usize,
+}
+
+impl<W> Move<W> {
+    pub fn new(item: W, target: usize) -> Self {
+        Self { item, target }
     }
+}
 
-    fn add_item(&mut self, item: usize) {
-        self.items.push(item)
+/// Parse the monkeys, which have to be at least two and may only throw to each other.
+fn parse_monkeys(input: &str) -> Result<Vec<Monkey<i128>>, ParseError> {
+    let notes = blocks(input)
+        .map(MonkeyNotes::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if notes.len() < 2 {
+        return Err(ParseError::missing(input, "at least two monkeys"));
     }
+
+    let num_monkeys = notes.len();
+
+    notes
+        .into_iter()
+        .map(|notes| {
+            for target in [notes.true_target, notes.false_target] {
+                if target.monkey >= num_monkeys {
+                    return Err(ParseError::new(target.text, format!("expected one of the monkeys 0 to {}", num_monkeys - 1)));
+                }
+            }
+
+            Ok(Monkey {
+                items: notes.items.into_iter().map(|item| item as i128).collect(),
+                operation: notes.operation,
+                test_value: notes.test_value,
+                true_target: notes.true_target.monkey,
+                false_target: notes.false_target.monkey,
+            })
+        })
+        .collect()
 }
 
-struct Move {
-    item: usize,
-    target: usize,
+/// The parsed description of a single monkey.
+struct MonkeyNotes<'a> {
+    items: Vec<usize>,
+    operation: Operation,
+    test_value: usize,
+    true_target: Target<'a>,
+    false_target: Target<'a>,
 }
 
-impl Move {
-    pub fn new(item: usize, target: usize) -> Self {
-        Self { item, target }
+/// The monkey an item is thrown to, with its text for error messages.
+#[derive(Copy, Clone)]
+struct Target<'a> {
+    monkey: usize,
+    text: &'a str,
+}
+
+impl<'a> Target<'a> {
+    fn parse(text: &'a str) -> Result<Self, ParseError> {
+        Ok(Target {
+            monkey: parse_number(text)?,
+            text,
+        })
     }
 }
 
-impl From<&str> for Monkey {
-    fn from(s: &str) -> Self {
-        let mut lines = s.lines().skip(1).map(|s| s.trim());
-        let items = to_item_list(lines.next().unwrap());
-        let operation = Operation::from(lines.next().unwrap());
-        let test_value = get_number_at_end(lines.next().unwrap());
-        let true_target = get_number_at_end(lines.next().unwrap());
-        let false_target = get_number_at_end(lines.next().unwrap());
+impl<'a> TryFrom<&'a str> for MonkeyNotes<'a> {
+    type Error = ParseError;
 
-        Monkey {
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        let mut lines = s.lines().map(|s| s.trim());
+        let mut next_line = |prefix: &str| {
+            let line = next_part(&mut lines, s, &format!("a line starting with '{prefix}'"))?;
+            line.strip_prefix(prefix).ok_or_else(|| ParseError::new(line, format!("expected '{prefix}'")))
+        };
+
+        next_line("Monkey ")?;
+        let items = to_item_list(next_line("Starting items: ")?)?;
+        let operation = next_line("Operation: new = ")?.parse()?;
+        let test_value_text = next_line("Test: divisible by ")?;
+        let test_value = parse_number(test_value_text)?;
+
+        if test_value == 0 {
+            return Err(ParseError::new(test_value_text, "expected a divisor greater than 0"));
+        }
+
+        let true_target = Target::parse(next_line("If true: throw to monkey ")?)?;
+        let false_target = Target::parse(next_line("If false: throw to monkey ")?)?;
+
+        Ok(MonkeyNotes {
             items,
             operation,
             test_value,
             true_target,
             false_target,
-        }
+        })
     }
 }
 
-fn to_item_list(line: &str) -> Vec<usize> {
+fn to_item_list(line: &str) -> Result<Vec<usize>, ParseError> {
     line
-        .replace("Starting items: ", "")
-        .replace(" ", "")
-        .split(",")
-        .map(|s| s.parse::<usize>().unwrap())
+        .split(", ")
+        .map(parse_number)
         .collect()
 }
 
-fn get_number_at_end(line: &str) -> usize {
-    line.split(" ").last().unwrap().parse::<usize>().unwrap()
+/// The right side of `new = ...`: two operands combined with an operator.
+#[derive(Debug, Eq, PartialEq)]
+struct Operation {
+    left: Operand,
+    operator: Operator,
+    right: Operand,
 }
 
-#[derive(Debug)]
-enum Operation {
-    Add(usize),
-    Times(usize),
-    Square,
+#[derive(Debug, Eq, PartialEq)]
+enum Operand {
+    Old,
+    Literal(usize),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Operator {
+    Add,
+    Sub,
+    Mul,
 }
 
 impl Operation {
-    fn calculate_new(&self, value: usize) -> usize {
-        match self {
-            Add(val) => value + val,
-            Times(val) => value * val,
-            Square => value * value,
-        }
+    fn calculate_new(&self, value: i128) -> i128 {
+        self.evaluate(value)
     }
 
-    fn calculate_new_stressful(&self, value: Remainders) -> Remainders {
-        match self {
-            Add(val) => value + Remainders::new(*val, &DIVIDERS),
-            Times(val) => value * Remainders::new(*val, &DIVIDERS),
-            Square => value.clone() * value,
-        }
+    /// The new value modulo the modulus.
+    fn calculate_new_modulo(&self, value: usize, modulus: usize) -> usize {
+        self.evaluate(value as i128).rem_euclid(modulus as i128) as usize
     }
-}
 
-impl From<&str> for Operation {
-    fn from(input: &str) -> Self {
-        let op = input.split("=").last().unwrap().replace(" ", "");
+    fn evaluate(&self, old: i128) -> i128 {
+        let operand = |operand: &Operand| match operand {
+            Old => old,
+            Literal(val) => *val as i128
+        };
 
-        match op.as_str() {
-            "old*old" => Square,
-            s if s.contains("*") => {
-                let num = s.split("*").last().unwrap().parse::<usize>().unwrap();
-                Times(num)
-            }
-            s if s.contains("+") => {
-                let num = s.split("+").last().unwrap().parse::<usize>().unwrap();
-                Add(num)
-            }
-            _ => panic!("unknown operation")
+        let (left, right) = (operand(&self.left), operand(&self.right));
+
+        match self.operator {
+            Operator::Add => left + right,
+            Operator::Sub => left - right,
+            Operator::Mul => left * right
         }
     }
 }
 
-struct StressMonkey {
-    items: Vec<Remainders>,
-    operation: Operation,
-    test_value: usize,
-    true_target: usize,
-    false_target: usize,
-}
+impl FromStr for Operation {
+    type Err = ParseError;
 
-impl StressMonkey {
-    fn process_items<'a>(&'a mut self) -> impl IntoIterator<Item=MoveStressful> + 'a {
-        let items = self.items.drain(..).collect::<Vec<_>>();
-        items.into_iter().map(|item| self.process_item(item))
-    }
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parts = input.split(' ');
 
-    fn process_item(&self, mut item: Remainders) -> MoveStressful {
-        self.inspect_current(&mut item);
+        let left = next_part(&mut parts, input, "'old' or a number")?.parse()?;
+        let operator = match next_part(&mut parts, input, "an operator")? {
+            "+" => Operator::Add,
+            "-" => Operator::Sub,
+            "*" => Operator::Mul,
+            op => return Err(ParseError::new(op, "unknown operator"))
+        };
+        let right = next_part(&mut parts, input, "'old' or a number")?.parse()?;
 
-        match self.check(&item) {
-            true => MoveStressful::new(item, self.true_target),
-            false => MoveStressful::new(item, self.false_target),
+        if let Some(extra) = parts.next() {
+            return Err(ParseError::new(extra, "unexpected text after the operation"));
         }
-    }
 
-    fn inspect_current(&self, item: &mut Remainders) {
-        *item = self.operation.calculate_new_stressful(item.clone())
+        Ok(Operation { left, operator, right })
     }
+}
 
-    fn check(&self, item: &Remainders) -> bool {
-        item.dividable_by(self.test_value)
-    }
+impl FromStr for Operand {
+    type Err = ParseError;
 
-    fn add_item(&mut self, item: Remainders) {
-        self.items.push(item)
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "old" => Ok(Old),
+            _ => input.parse().map(Literal).map_err(|_| ParseError::new(input, "expected 'old' or a number"))
+        }
     }
 }
 
-impl From<&str> for StressMonkey {
-    fn from(s: &str) -> Self {
-        let mut lines = s.lines().skip(1).map(|s| s.trim());
-        let items = to_item_list_stress(lines.next().unwrap());
-        let operation = Operation::from(lines.next().unwrap());
-        let test_value = get_number_at_end(lines.next().unwrap());
-        let true_target = get_number_at_end(lines.next().unwrap());
-        let false_target = get_number_at_end(lines.next().unwrap());
+#[cfg(test)]
+mod tests {
+    use crate::p11::{lcm, Modular, Operation, parse_monkeys, Relief, Runner, with_modular_worry, Worry};
 
-        StressMonkey {
-            items,
-            operation,
-            test_value,
-            true_target,
-            false_target,
-        }
+    #[test]
+    fn operations_work() {
+        [
+            ("old * old", 49),
+            ("old * 3", 21),
+            ("3 * old", 21),
+            ("old + 3", 10),
+            ("3 + old", 10),
+            ("old + old", 14),
+            ("old - 3", 4),
+            ("10 - old", 3),
+            ("old - old", 0),
+            ("2 * 3", 6),
+        ].into_iter().for_each(|(operation, expected)| {
+            let operation = operation.parse::<Operation>().unwrap();
+            assert_eq!(operation.calculate_new(7), expected);
+            assert_eq!(operation.calculate_new_modulo(7, 5), expected as usize % 5);
+        })
     }
-}
 
-fn to_item_list_stress(line: &str) -> Vec<Remainders> {
-    line
-        .replace("Starting items: ", "")
-        .replace(" ", "")
-        .split(",")
-        .map(|s| s.parse::<usize>().unwrap())
-        .map(|val| Remainders::new(val, &DIVIDERS))
-        .collect()
-}
+    #[test]
+    fn subtraction_works_in_part_1() {
+        let input = "Monkey 0:
+  Starting items: 5, 20
+  Operation: new = old - 10
+  Test: divisible by 3
+    If true: throw to monkey 1
+    If false: throw to monkey 1
 
-struct MoveStressful {
-    item: Remainders,
-    target: usize,
-}
+Monkey 1:
+  Starting items: 7
+  Operation: new = old * 2
+  Test: divisible by 2
+    If true: throw to monkey 0
+    If false: throw to monkey 0";
 
-impl MoveStressful {
-    pub fn new(item: Remainders, target: usize) -> Self {
-        Self { item, target }
+        let mut runner = Runner::new(parse_monkeys(input).unwrap(), Relief::divide_by(3), 20);
+        runner.run();
+
+        // (5 - 10) / 3 is rounded down to -2, the worry levels stay negative
+        assert_eq!(runner.monkeys[0].items, vec![-4, -4, -4]);
+        assert_eq!(runner.calculate_monkey_business(), 59 * 60);
     }
-}
 
-#[derive(Clone, Debug)]
-struct Remainders {
-    remainders: HashMap<usize, usize>,
-}
+    #[test]
+    fn negative_operations_wrap_around_the_modulus() {
+        let operation = "old - 10".parse::<Operation>().unwrap();
+        assert_eq!(operation.calculate_new_modulo(3, 12), 5);
 
-// const DIVIDERS: [usize; 4] = [23, 19, 13, 17];
-const DIVIDERS: [usize; 8] = [2, 3, 5, 7, 11, 13, 17, 19];
+        let operation = "2 - old".parse::<Operation>().unwrap();
+        assert_eq!(operation.calculate_new_modulo(3, 12), 11);
+    }
 
-impl Remainders {
-    fn new(input: usize, dividers: &[usize]) -> Self {
-        Remainders {
-            remainders: dividers
-                .into_iter()
-                .map(|div| (*div, input % div))
-                .collect()
-        }
+    #[test]
+    fn invalid_operations_are_rejected() {
+        [
+            ("old / 2", "unknown operator '/'"),
+            ("new * 2", "expected 'old' or a number 'new'"),
+            ("old * -2", "expected 'old' or a number '-2'"),
+            ("old *", "expected 'old' or a number"),
+            ("old * 2 + 1", "unexpected text after the operation '+'"),
+        ].into_iter().for_each(|(operation, expected)| {
+            assert_eq!(operation.parse::<Operation>().unwrap_err().to_string(), expected)
+        })
     }
 
-    fn dividable_by(&self, val: usize) -> bool {
-        match self.remainders.get(&val) {
-            None => false,
-            Some(rem) => *rem == 0
-        }
+    #[test]
+    fn lcm_works() {
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!([23, 19, 13, 17].into_iter().fold(1, lcm), 96577);
+        assert_eq!([2, 4, 8].into_iter().fold(1, lcm), 8);
     }
-}
 
-// (a + b) mod n = (a mod n + b mod n) mod n
-impl std::ops::Add for Remainders {
-    type Output = Remainders;
+    #[test]
+    fn modular_worry_works() {
+        let input = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * old
+  Test: divisible by 6
+    If true: throw to monkey 1
+    If false: throw to monkey 1
 
-    fn add(self, other: Self) -> Self::Output {
-        Remainders {
-            remainders: self.remainders
-                .iter()
-                .map(|(div, remain)| (*div, (remain + other.remainders.get(&div).unwrap()) % *div))
-                .collect::<HashMap<_, _>>()
-        }
-    }
-}
+Monkey 1:
+  Starting items: 31
+  Operation: new = old + 3
+  Test: divisible by 4
+    If true: throw to monkey 0
+    If false: throw to monkey 0";
 
-// (a * b) mod n = (a mod n) * (b mod n) mod n
-impl Mul for Remainders {
-    type Output = Remainders;
+        let monkeys = with_modular_worry(parse_monkeys(input).unwrap());
+        assert_eq!(monkeys[0].items, vec![Modular { value: 7, modulus: 12 }, Modular { value: 2, modulus: 12 }]);
 
-    fn mul(self, other: Self) -> Self::Output {
-        Remainders {
-            remainders: self.remainders
-                .iter()
-                .map(|(div, remain)| (*div, (remain * other.remainders.get(&div).unwrap()) % *div))
-                .collect::<HashMap<_, _>>()
-        }
+        let item = monkeys[0].items[0].inspect(&monkeys[0].operation);
+        assert_eq!(item, Modular { value: 1, modulus: 12 });
+        assert_eq!(monkeys[1].items[0].inspect(&monkeys[1].operation), Modular { value: 10, modulus: 12 });
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::p11::{Monkey, DIVIDERS, Remainders, Runner, StressMonkey, StressRunner};
 
     #[test]
-    fn create_remainders_works() {
-        let remainders = Remainders::new(79, &DIVIDERS);
-        println!("{:?}", remainders)
+    fn zero_divisor_is_rejected() {
+        let input = "Monkey 0:
+  Starting items: 1
+  Operation: new = old * old
+  Test: divisible by 0
+    If true: throw to monkey 0
+    If false: throw to monkey 0";
+
+        assert_eq!(parse_monkeys(input).err().unwrap().locate(input).to_string(), "line 4, column 22: expected a divisor greater than 0 '0'");
     }
 
     #[test]
-    fn add_remainders_works() {
-        let one = Remainders::new(54, &DIVIDERS);
-        let two = Remainders::new(6, &DIVIDERS);
-        let add = one + two;
-        println!("{:?}", add)
+    fn unknown_targets_are_rejected() {
+        let input = "Monkey 0:
+  Starting items: 1
+  Operation: new = old * old
+  Test: divisible by 2
+    If true: throw to monkey 1
+    If false: throw to monkey 0
+
+Monkey 1:
+  Starting items: 2
+  Operation: new = old + 1
+  Test: divisible by 3
+    If true: throw to monkey 0
+    If false: throw to monkey 2";
+
+        assert_eq!(parse_monkeys(input).err().unwrap().locate(input).to_string(), "line 13, column 31: expected one of the monkeys 0 to 1 '2'");
     }
 
     #[test]
-    fn mul_remainders_works() {
-        let one = Remainders::new(54, &DIVIDERS);
-        let two = Remainders::new(6, &DIVIDERS);
-        let mul = one * two;
-        println!("{:?}", mul)
+    fn single_monkey_is_rejected() {
+        let input = "Monkey 0:
+  Starting items: 1
+  Operation: new = old * old
+  Test: divisible by 2
+    If true: throw to monkey 0
+    If false: throw to monkey 0";
+
+        assert_eq!(parse_monkeys(input).err().unwrap().locate(input).to_string(), "line 6, column 32: expected at least two monkeys");
+        assert!(parse_monkeys("").is_err());
     }
 
     #[test]
@@ -414,12 +561,54 @@ Monkey 3:
     If true: throw to monkey 0
     If false: throw to monkey 1";
 
-        let mut runner = Runner::new(input.split("\n\n").map(|s| Monkey::from(s)));
+        let mut runner = Runner::new(parse_monkeys(input).unwrap(), Relief::divide_by(3), 20);
         runner.run();
         assert_eq!(runner.calculate_monkey_business(), 10605);
 
-        let mut runner = StressRunner::new(input.split("\n\n").map(|s| StressMonkey::from(s)));
+        let mut runner = Runner::new(with_modular_worry(parse_monkeys(input).unwrap()), Relief::none(), 10000);
         runner.run();
         assert_eq!(runner.calculate_monkey_business(), 2713310158);
     }
+
+    #[test]
+    fn custom_relief_works() {
+        let input = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+
+        // Keeping the worry below the product of the divisors doesn't change the tests
+        let relief = || Relief::custom(|worry| worry % (23 * 19 * 13 * 17));
+
+        let mut runner = Runner::new(parse_monkeys(input).unwrap(), relief(), 20);
+        runner.run();
+        assert_eq!(runner.inspections, vec![99, 97, 8, 103]);
+
+        let mut runner = Runner::new(parse_monkeys(input).unwrap(), relief(), 1000);
+        runner.run();
+        assert_eq!(runner.calculate_monkey_business(), 5204 * 5192);
+    }
 }
\ No newline at end of file
@@ -1,188 +1,284 @@
-use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use pad::Position;
-use crate::input_reader::read_input;
+use crate::grid::Grid;
 use crate::p12::Field::{End, Start, Val};
+use crate::parse::ParseError;
+use crate::search::{bfs, Search};
+use crate::show::{ShowError, ShowOptions};
+use crate::solver::{Answer, Solver};
 
-pub fn solve_p12() {
-    let input = read_input(12);
+pub struct P12;
 
-    let board = Board::from(input.as_str());
+impl Solver for P12 {
+    fn day(&self) -> usize {
+        12
+    }
+
+    fn title(&self) -> &'static str {
+        "Hill Climbing Algorithm"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        Board::try_from(input).map(drop)
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let len_shortest_path = Board::try_from(input)?
+            .get_len_shortest_path()
+            .ok_or_else(|| ParseError::missing(input, "a path from S to E"))?;
+        Ok(len_shortest_path.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let len_shortest_hiking_path = Board::try_from(input)?
+            .get_len_shortest_hiking_path()
+            .ok_or_else(|| ParseError::missing(input, "a path from elevation a to E"))?;
+        Ok(len_shortest_hiking_path.into())
+    }
+
+    /// Prints the shortest path of the part as arrows. With `ppm=FILE` the heightmap and path
+    /// are also written as plain PPM image, every field `scale` pixels wide.
+    fn show(&self, input: &str, options: &ShowOptions, out: &mut dyn Write) -> Result<(), ShowError> {
+        options.check_known(&["part", "ppm", "scale"])?;
+        let board = Board::try_from(input)?;
+
+        let routes = board.routes();
+
+        let path = match options.get_or("part", 1)? {
+            1 => routes.shortest_path(),
+            2 => routes.shortest_hiking_path(),
+            part => return Err(ShowError::Option(format!("unknown part {part}")))
+        }.ok_or_else(|| ShowError::Option("the end can't be reached".to_string()))?;
 
-    let len_shortest_path = board.get_len_shortest_path();
-    println!("Solution 1: {len_shortest_path}");
+        writeln!(out, "{}", board.render_path(&path))?;
 
-    let len_shortest_hiking_path = board.get_len_shortest_hiking_path();
-    println!("Solution 2: {len_shortest_hiking_path}");
+        if let Some(file) = options.get("ppm") {
+            let mut writer = BufWriter::new(File::create(file)?);
+            board.write_ppm(&path, options.get_or("scale", 4)?, &mut writer)?;
+            writer.flush()?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
 struct Board {
-    fields: HashMap<Position, Field>,
+    fields: Grid<Field>,
 }
 
 impl Board {
-    fn get_len_shortest_path(&self) -> usize {
-        let start = self.fields
-            .iter()
-            .filter_map(|(pos, field)| if let Start = field {
-                Some(pos)
-            } else {
-                None
-            })
-            .next().unwrap();
-
-        let goal = self.fields
-            .iter()
-            .filter_map(|(pos, field)| if let End = field {
-                Some(pos)
-            } else {
-                None
-            })
-            .next().unwrap();
+    fn get_len_shortest_path(&self) -> Option<usize> {
+        self.routes().len_shortest_path()
+    }
 
-        let path = self.a_star(*start, *goal);
-        path.unwrap().len() - 1
+    fn get_len_shortest_hiking_path(&self) -> Option<usize> {
+        self.routes().len_shortest_hiking_path()
     }
 
-    fn get_len_shortest_hiking_path(&self) -> usize {
-        let ground_nodes = self.fields
-            .iter()
-            .filter_map(|(pos, field)| match field {
-                Start | Val(1) => Some(pos),
-                _ => None
-            });
+    /// The shortest routes to the end from every field. Searches backwards from the end, so it
+    /// takes only one pass.
+    fn routes(&self) -> Routes {
+        let end = self.position_of(&End);
+        let search = bfs([end], |pos| self.predecessors_of(*pos).collect::<Vec<_>>());
+        let distances = Grid::from_fn(self.fields.width(), self.fields.height(), |pos| search.distance(&pos));
 
-        let goal = self.fields
+        let hiking_start = self.fields
             .iter()
-            .filter_map(|(pos, field)| if let End = field {
-                Some(pos)
-            } else {
-                None
-            })
-            .next().unwrap();
-
-        ground_nodes
-            .flat_map(|pos| self.a_star(*pos, *goal))
-            .map(|path| path.len() - 1)
-            .min()
-            .unwrap()
+            .filter(|(_, field)| matches!(field, Start | Val(1)))
+            .filter_map(|(pos, _)| distances[pos].map(|distance| (distance, pos)))
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, pos)| pos);
+
+        Routes {
+            start: self.position_of(&Start),
+            hiking_start,
+            distances,
+            search,
+        }
     }
 
-    fn a_star(&self, start: Position, goal: Position) -> Option<Vec<Position>> {
-        let mut open_set: HashSet<Position> = HashSet::new();
-        open_set.insert(start);
+    /// Draw the path like the puzzle description: every step is an arrow pointing to the
+    /// next field, the end is 'E' and all other fields are '.'.
+    fn render_path(&self, path: &[Position]) -> Grid<char> {
+        let mut rendered = Grid::new(self.fields.width(), self.fields.height(), '.');
 
-        let mut came_from: HashMap<Position, Position> = HashMap::new();
+        for step in path.windows(2) {
+            let (from, to) = (step[0], step[1]);
 
-        let mut g_score: HashMap<Position, usize> = HashMap::new();
-        g_score.insert(start, 0);
+            rendered[from] = match (to.x - from.x, to.y - from.y) {
+                (1, _) => '>',
+                (-1, _) => '<',
+                (_, 1) => 'v',
+                _ => '^'
+            };
+        }
 
-        let mut f_score: HashMap<Position, usize> = HashMap::new();
-        f_score.insert(start, h(start, goal));
+        if let Some(last) = path.last() {
+            rendered[*last] = 'E'
+        }
 
-        while !open_set.is_empty() {
-            let current = open_set
+        rendered
+    }
+
+    /// Write the heightmap as plain PPM image: elevation from dark to light green,
+    /// the path in red, start in blue and end in yellow.
+    fn write_ppm(&self, path: &[Position], scale: usize, out: &mut impl Write) -> std::io::Result<()> {
+        let scale = scale.max(1);
+        writeln!(out, "P3")?;
+        writeln!(out, "{} {}", self.fields.width() * scale, self.fields.height() * scale)?;
+        writeln!(out, "255")?;
+
+        for (y, row) in self.fields.rows().enumerate() {
+            let colors = row
                 .iter()
-                .map(|pos| (*pos, f_score.get(pos).unwrap()))
-                .min_by(|(_, val_one), (_, val_two)| val_one.cmp(&val_two))
-                .unwrap().0;
+                .enumerate()
+                .map(|(x, field)| match field {
+                    Start => (0, 0, 255),
+                    End => (255, 255, 0),
+                    _ if path.contains(&Position::new(x as isize, y as isize)) => (255, 0, 0),
+                    Val(elevation) => {
+                        let level = elevation * 255 / 26;
+                        (level / 2, level, level / 2)
+                    }
+                })
+                .flat_map(|(r, g, b)| std::iter::repeat_n([r, g, b], scale).flatten())
+                .collect::<Vec<_>>();
 
-            if current == goal {
-                return Some(Self::construct_path(came_from, current));
+            for _ in 0..scale {
+                write_wrapped(&colors, out)?;
             }
+        }
 
-            open_set.remove(&current);
+        Ok(())
+    }
 
-            for neigh in self.neighbours_of(current) {
-                let tentative_g_score = g_score.get(&current).unwrap() + 1;
+    fn position_of(&self, field: &Field) -> Position {
+        self.fields
+            .find(|f| f == field)
+            .expect("the field should exist")
+    }
 
-                if tentative_g_score < *g_score.get(&neigh).unwrap_or(&usize::MAX) {
-                    came_from.insert(neigh, current);
-                    g_score.insert(neigh, tentative_g_score);
-                    f_score.insert(neigh, tentative_g_score + h(neigh, goal));
+    /// The fields from which the position can be climbed to.
+    fn predecessors_of(&self, position: Position) -> impl Iterator<Item=Position> + '_ {
+        self.fields
+            .neighbours_4(position)
+            .filter(move |pos| can_climb(&self.fields[*pos], &self.fields[position]))
+    }
+}
 
-                    if !open_set.contains(&neigh) {
-                        open_set.insert(neigh);
-                    }
-                }
-            }
+/// Write the values separated by spaces, starting a new line before one would get longer than
+/// the 70 characters allowed in plain PPM files. Every row of pixels starts on a new line.
+fn write_wrapped(values: &[usize], out: &mut impl Write) -> std::io::Result<()> {
+    const MAX_LINE_LEN: usize = 70;
+    let mut line_len = 0;
+
+    for value in values {
+        let value = value.to_string();
+
+        if line_len > 0 && line_len + 1 + value.len() > MAX_LINE_LEN {
+            writeln!(out)?;
+            line_len = 0;
         }
 
-        None
+        if line_len > 0 {
+            write!(out, " ")?;
+            line_len += 1;
+        }
+
+        write!(out, "{value}")?;
+        line_len += value.len();
     }
 
-    fn construct_path(came_from: HashMap<Position, Position>, mut current: Position) -> Vec<Position> {
-        let mut total_path = vec![current];
+    writeln!(out)
+}
 
-        while came_from.contains_key(&current) {
-            current = *came_from.get(&current).unwrap();
-            total_path.push(current);
-        }
+/// The result of the search backwards from the end.
+struct Routes {
+    search: Search<Position>,
+    /// The length of the shortest path from every field to the end, None if the end
+    /// can't be reached.
+    distances: Grid<Option<usize>>,
+    start: Position,
+    /// The lowest field closest to the end, None if the end can't be reached from any.
+    hiking_start: Option<Position>,
+}
 
-        total_path
-    }
-
-    fn neighbours_of<'a>(&'a self, position: Position) -> impl IntoIterator<Item=Position> + 'a {
-        position.neighbours()
-            .into_iter()
-            .map(|n| n.position)
-            .filter(|pos| self.fields.contains_key(&pos))
-            .filter(move |pos| {
-                let current = self.fields.get(&position).unwrap();
-                let neigh = self.fields.get(&pos).unwrap();
-
-                match (current, neigh) {
-                    (Val(a), Val(b)) => match a < b {
-                        true => (b - a) <= 1,
-                        false => true
-                    },
-                    (_, Start) => false,
-                    (Start, _) => true,
-                    (Val(a), End) => a.abs_diff(26) <= 1,
-                    (End, _) => false,
-                }
-            })
+impl Routes {
+    fn len_shortest_path(&self) -> Option<usize> {
+        self.distances[self.start]
+    }
+
+    fn len_shortest_hiking_path(&self) -> Option<usize> {
+        self.distances[self.hiking_start?]
+    }
+
+    /// The shortest path from the start to the end, both included.
+    fn shortest_path(&self) -> Option<Vec<Position>> {
+        self.path_to_end(self.start)
+    }
+
+    /// The shortest path from any field with the lowest elevation to the end.
+    fn shortest_hiking_path(&self) -> Option<Vec<Position>> {
+        self.path_to_end(self.hiking_start?)
+    }
+
+    fn path_to_end(&self, start: Position) -> Option<Vec<Position>> {
+        let mut path = self.search.path_to(&start)?;
+        path.reverse();
+        Some(path)
     }
 }
 
-fn h(current: Position, end: Position) -> usize {
-    (current.x.abs_diff(end.x) + current.y.abs_diff(end.y)) as usize
+fn can_climb(from: &Field, to: &Field) -> bool {
+    match (from, to) {
+        (Val(a), Val(b)) => match a < b {
+            true => (b - a) <= 1,
+            false => true
+        },
+        (_, Start) => false,
+        (Start, _) => true,
+        (Val(a), End) => a.abs_diff(26) <= 1,
+        (End, _) => false,
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq)]
 enum Field {
     Val(usize),
     Start,
     End,
 }
 
-impl From<&str> for Board {
-    fn from(input: &str) -> Self {
-        let height = input.lines().count();
+impl TryFrom<&str> for Board {
+    type Error = ParseError;
 
-        let fields = input.lines()
-            .enumerate()
-            .flat_map(|(y, line)| line
-                .chars()
-                .enumerate()
-                .map(move |(x, c)| (Position::from((x, height - 1 - y)), match c {
-                    'a'..='z' => Val(c as usize - 96),
-                    'S' => Start,
-                    'E' => End,
-                    _ => panic!("unknown")
-                }))
-            )
-            .collect();
-
-        Board {
-            fields
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let fields = Grid::parse(input, "a height, 'S' or 'E'", |c| match c {
+            'a'..='z' => Some(Val(c as usize - 96)),
+            'S' => Some(Start),
+            'E' => Some(End),
+            _ => None
+        })?;
+
+        for (field, name) in [(Start, "a start 'S'"), (End, "an end 'E'")] {
+            if fields.iter().filter(|(_, f)| **f == field).count() != 1 {
+                return Err(ParseError::missing(input, &format!("exactly one {name}")));
+            }
         }
+
+        Ok(Board {
+            fields
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::p12::Board;
+    use pad::Position;
+    use crate::p12::{Board, P12};
+    use crate::solver::{Part, solve};
 
     #[test]
     fn examples_work() {
@@ -192,10 +288,91 @@ accszExk
 acctuvwj
 abdefghi";
 
-        let board = Board::from(input);
+        let board = Board::try_from(input).unwrap();
         println!("{:?}", board);
 
-        assert_eq!(board.get_len_shortest_path(), 31);
-        assert_eq!(board.get_len_shortest_hiking_path(), 29);
+        assert_eq!(board.get_len_shortest_path(), Some(31));
+        assert_eq!(board.get_len_shortest_hiking_path(), Some(29));
+    }
+
+    #[test]
+    fn unreachable_end_is_an_error() {
+        let error = solve(&P12, Part::One, "SbE").unwrap_err();
+        assert_eq!(error.to_string(), "day 12, line 1, column 4: expected a path from S to E");
+
+        let error = solve(&P12, Part::Two, "SbE").unwrap_err();
+        assert_eq!(error.to_string(), "day 12, line 1, column 4: expected a path from elevation a to E");
+    }
+
+    #[test]
+    fn distances_to_end_work() {
+        let input = "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi";
+
+        let board = Board::try_from(input).unwrap();
+        let distances = board.routes().distances;
+
+        assert_eq!(distances[Position::new(0, 0)], Some(31));
+        assert_eq!(distances[Position::new(5, 2)], Some(0));
+        assert_eq!(distances[Position::new(4, 2)], Some(1));
+        assert_eq!(distances[Position::new(0, 4)], Some(29));
+    }
+
+    #[test]
+    fn render_path_works() {
+        let input = "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi";
+
+        let board = Board::try_from(input).unwrap();
+
+        let routes = board.routes();
+        let path = routes.shortest_path().unwrap();
+        assert_eq!(path.len(), 32);
+        assert_eq!(board.render_path(&path).to_string(), ">>vv<<<<
+..vvv<<^
+..vv>E^^
+..v>>>^^
+..>>>>>^");
+
+        assert_eq!(routes.shortest_hiking_path().unwrap().len(), 30);
+    }
+
+    #[test]
+    fn write_ppm_works() {
+        let board = Board::try_from("Syz\nabE").unwrap();
+        let path = board.routes().shortest_path().unwrap();
+
+        let mut out = vec![];
+        board.write_ppm(&path, 1, &mut out).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "P3
+3 2
+255
+0 0 255 255 0 0 255 0 0
+4 9 4 9 19 9 255 255 0
+");
+    }
+
+    #[test]
+    fn write_ppm_wraps_long_lines() {
+        let board = Board::try_from("Syz\nabE").unwrap();
+        let path = board.routes().shortest_path().unwrap();
+
+        let mut out = vec![];
+        board.write_ppm(&path, 4, &mut out).unwrap();
+        let ppm = String::from_utf8(out).unwrap();
+
+        assert!(ppm.lines().all(|line| line.len() <= 70));
+        assert_eq!(ppm.lines().skip(3).take(2).collect::<Vec<_>>(), [
+            "0 0 255 0 0 255 0 0 255 0 0 255 255 0 0 255 0 0 255 0 0 255 0 0 255 0",
+            "0 255 0 0 255 0 0 255 0 0",
+        ]);
+        assert_eq!(ppm.split_whitespace().skip(4).count(), 12 * 8 * 3);
     }
 }
\ No newline at end of file
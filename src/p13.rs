@@ -1,50 +1,75 @@
 use std::cmp::Ordering;
 use std::cmp::Ordering::*;
-use crate::input_reader::read_input;
+use std::str::FromStr;
 use crate::p13::Value::{Int, List};
+use crate::input_reader::blocks;
+use crate::parse::{next_part, parse_number, ParseError};
+use crate::solver::{Answer, Solver};
 
-pub fn solve_p13() {
-    let input = read_input(13);
+pub struct P13;
 
-    let sum_right_ordered_indices = sum_right_ordered_indices(input.as_str());
-    println!("Solution 1: {sum_right_ordered_indices}");
+impl Solver for P13 {
+    fn day(&self) -> usize {
+        13
+    }
+
+    fn title(&self) -> &'static str {
+        "Distress Signal"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_pairs(input).map(drop)
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let sum_right_ordered_indices = sum_right_ordered_indices(input)?;
+        Ok(sum_right_ordered_indices.into())
+    }
 
-    let decoder_key = compute_decoder_key(input.as_str());
-    println!("Solution 2: {decoder_key}");
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let decoder_key = compute_decoder_key(input)?;
+        Ok(decoder_key.into())
+    }
 }
 
-fn sum_right_ordered_indices(input: &str) -> usize {
-    input.replace("\r", "").split("\n\n")
+fn parse_pairs(input: &str) -> Result<Vec<ValuePair>, ParseError> {
+    blocks(input)
         .map(|block| {
             let mut lines = block.lines();
-            ValuePair::new(
-                Value::from(lines.next().unwrap()),
-                Value::from(lines.next().unwrap()),
-            )
+            Ok(ValuePair::new(
+                next_part(&mut lines, block, "a packet")?.parse()?,
+                next_part(&mut lines, block, "a second packet")?.parse()?,
+            ))
         })
+        .collect()
+}
+
+fn sum_right_ordered_indices(input: &str) -> Result<usize, ParseError> {
+    Ok(parse_pairs(input)?
+        .into_iter()
         .map(|pair| pair.is_ordered())
         .enumerate()
         .filter_map(|(i, ordered)| match ordered {
             true => Some(i + 1),
             false => None
         })
-        .sum()
+        .sum())
 }
 
-fn compute_decoder_key(input: &str) -> usize {
+fn compute_decoder_key(input: &str) -> Result<usize, ParseError> {
     let mut index_values = ["[[2]]", "[[6]]"]
         .into_iter()
-        .chain(input.replace("\r", "").replace("\n\n", "\n").lines())
-        .map(Value::from)
-        .collect::<Vec<_>>();
+        .chain(input.lines().filter(|line| !line.is_empty()))
+        .map(Value::from_str)
+        .collect::<Result<Vec<_>, _>>()?;
 
     index_values.sort();
 
-    index_values.into_iter()
+    Ok(index_values.into_iter()
         .enumerate()
         .filter(|(_, val)| val == &List(vec![List(vec![Int(2)])]) || val == &List(vec![List(vec![Int(6)])]))
         .map(|(i, _)| i + 1)
-        .product()
+        .product())
 }
 
 #[derive(Debug)]
@@ -103,40 +128,44 @@ fn compare_lists(a: Vec<Value>, b: Vec<Value>) -> Option<Ordering> {
     Some(a.len().cmp(&b.len()))
 }
 
-impl From<&str> for Value {
-    fn from(input: &str) -> Self {
-        if input.starts_with("[") {
-            let mut level = 0;
-
-            let cleaned = input.chars()
-                .into_iter()
-                .map(|c| match c {
-                    '[' => {
-                        level += 1;
-                        match level {
-                            1 => ' ',
-                            _ => c
-                        }
-                    }
-                    ']' => {
-                        level -= 1;
-                        match level {
-                            0 => ' ',
-                            _ => c
-                        }
-                    }
-                    ',' => match level {
-                        1 => '|',
-                        _ => c
-                    },
-                    _ => c
-                }).collect::<String>().replace(" ", "");
-
-            let split = cleaned.split("|");
-
-            List(split.into_iter().filter(|s| !s.is_empty()).map(Value::from).collect())
-        } else {
-            Int(input.parse::<usize>().unwrap())
+impl FromStr for Value {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (value, rest) = parse_value(input)?;
+
+        match rest.is_empty() {
+            true => Ok(value),
+            false => Err(ParseError::new(rest, "unexpected input after packet"))
+        }
+    }
+}
+
+/// Parse the value at the start of the input. Returns the value and the remaining input.
+fn parse_value(input: &str) -> Result<(Value, &str), ParseError> {
+    let mut rest = match input.strip_prefix('[') {
+        Some(rest) => rest,
+        None => {
+            let end = input.find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len());
+            return Ok((Int(parse_number(&input[..end])?), &input[end..]));
+        }
+    };
+
+    let mut values = vec![];
+
+    if let Some(rest) = rest.strip_prefix(']') {
+        return Ok((List(values), rest));
+    }
+
+    loop {
+        let (value, after_value) = parse_value(rest)?;
+        values.push(value);
+
+        match after_value.chars().next() {
+            Some(',') => rest = &after_value[1..],
+            Some(']') => return Ok((List(values), &after_value[1..])),
+            Some(c) => return Err(ParseError::new(&after_value[..c.len_utf8()], "expected ',' or ']'")),
+            None => return Err(ParseError::missing(input, "']'"))
         }
     }
 }
@@ -149,13 +178,24 @@ mod tests {
     #[test]
     fn value_from_str_works() {
         let example = "[[1],[2,3,4]]";
-        let val = Value::from(example);
+        let val = example.parse::<Value>().unwrap();
         assert_eq!(val, List(vec![
             List(vec![Int(1)]),
             List(vec![Int(2), Int(3), Int(4)]),
         ]))
     }
 
+    #[test]
+    fn value_from_invalid_str_fails() {
+        let input = "[1,[2;3]]";
+        let err = input.parse::<Value>().unwrap_err().locate(input);
+        assert_eq!(err.to_string(), "line 1, column 6: expected ',' or ']' ';'");
+
+        let input = "[1,[2,3]";
+        let err = input.parse::<Value>().unwrap_err().locate(input);
+        assert_eq!(err.to_string(), "line 1, column 9: expected ']'");
+    }
+
     #[test]
     fn examples_work() {
         let input = "[1,1,3,1,1]
@@ -182,10 +222,10 @@ mod tests {
 [1,[2,[3,[4,[5,6,7]]]],8,9]
 [1,[2,[3,[4,[5,6,0]]]],8,9]";
 
-        let sum = sum_right_ordered_indices(input);
+        let sum = sum_right_ordered_indices(input).unwrap();
         assert_eq!(sum, 13);
 
-        let key = compute_decoder_key(input);
+        let key = compute_decoder_key(input).unwrap();
         assert_eq!(key, 140);
     }
 }
\ No newline at end of file
@@ -1,148 +1,438 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::Duration;
 use pad::{Position, PositionIter};
-use pad::Direction::{Left, Right, Up};
-use crate::input_reader::read_input;
+use crate::grid::Grid;
 use crate::p14::Field::*;
-use crate::p14::SandUpdate::*;
+use crate::parse::{next_part, parse_number, ParseError};
+use crate::show::{ShowError, ShowOptions};
+use crate::solver::{Answer, Solver};
 
-pub fn solve_p14() {
-    let input = read_input(14);
-    let mut board = Board::from(input.as_str());
+pub struct P14;
 
-    let fallen_sand = board.run_sand();
-    println!("Solution 1: {fallen_sand}");
+impl Solver for P14 {
+    fn day(&self) -> usize {
+        14
+    }
+
+    fn title(&self) -> &'static str {
+        "Regolith Reservoir"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_rocks(input).map(drop)
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let mut board = Board::new(&parse_rocks(input)?, SandConfig::default());
+
+        let fallen_sand = board.run_sand();
+        Ok(fallen_sand.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let mut board = Board::new(&parse_rocks(input)?, SandConfig::with_floor());
+        let fallen_sand = board.run_sand();
+        Ok(fallen_sand.into())
+    }
+
+    /// Plays the simulation in the terminal. Options: `part=1|2`, `every=grain|step` (when to
+    /// draw a frame), `speed=N` (frames per second, 0 for no delay) and `rows=N` (height of
+    /// the viewport, which follows the sand). The sand of the part can be changed with
+    /// `spawns=X,Y:X,Y`, `floor=N|none` (rows below the lowest rock), `floor_columns=MIN..=MAX`
+    /// and `fall=DX,DY:DX,DY` (the directions a grain tries in order).
+    fn show(&self, input: &str, options: &ShowOptions, out: &mut dyn Write) -> Result<(), ShowError> {
+        options.check_known(&["part", "every", "speed", "rows", "spawns", "floor", "floor_columns", "fall"])?;
+        let rocks = parse_rocks(input)?;
+
+        let mut config = match options.get_or("part", 1)? {
+            1 => SandConfig::default(),
+            2 => SandConfig::with_floor(),
+            part => return Err(ShowError::Option(format!("unknown part {part}")))
+        };
+
+        if let Some(spawns) = options.get("spawns") {
+            config.spawns = parse_pairs(spawns, "spawns")?.into_iter().map(|(x, y)| Position::new(x, y)).collect();
+        }
+
+        match options.get("floor") {
+            Some("none") => config.floor_offset = None,
+            Some(_) => config.floor_offset = Some(options.get_or("floor", FLOOR_OFFSET)?),
+            None => ()
+        }
+
+        if options.get("floor_columns").is_some() {
+            config.floor_columns = Some(options.get_range_or("floor_columns", 0..=0)?);
+        }
+
+        if let Some(fall) = options.get("fall") {
+            config.fall_directions = parse_pairs(fall, "fall")?;
+        }
+
+        let every_step = match options.get("every").unwrap_or("grain") {
+            "grain" => false,
+            "step" => true,
+            every => return Err(ShowError::Option(format!("unknown value '{every}' for every, expected grain or step")))
+        };
 
-    let mut board = Board::from(input.as_str());
-    board.add_floor();
-    let fallen_sand = board.run_sand();
-    println!("Solution 2: {fallen_sand}");
+        let delay = match options.get_or("speed", 20)? {
+            0 => Duration::ZERO,
+            fps => Duration::from_secs(1) / fps
+        };
+
+        config.check().map_err(ShowError::Option)?;
+
+        let mut board = Board::new(&rocks, config);
+        play(&mut board, every_step, delay, options.get_or("rows", 40)?, out)?;
+        Ok(())
+    }
+}
+
+/// Parse a list like `500,0:510,0`.
+fn parse_pairs(value: &str, key: &str) -> Result<Vec<(isize, isize)>, ShowError> {
+    value
+        .split(':')
+        .map(|pair| pair
+            .split_once(',')
+            .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?))))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| ShowError::Option(format!("invalid value '{value}' for {key}, expected X,Y:X,Y")))
+}
+
+/// The floor in part 2 lies this many rows below the lowest rock.
+const FLOOR_OFFSET: isize = 2;
+
+/// How the sand pours and falls. The default is part 1: one spawn at (500, 0), no floor and
+/// falling down, down-left and down-right.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct SandConfig {
+    /// The grains spawn at these positions in turn, a blocked spawn is skipped.
+    spawns: Vec<Position>,
+    /// The floor lies this many rows below the lowest rock, at least 1. Without a floor sand
+    /// falls into the abyss once it passes the lowest rock.
+    floor_offset: Option<isize>,
+    /// The columns covered by the floor, None if it is infinite. Sand falls into the abyss
+    /// next to a bounded floor.
+    floor_columns: Option<RangeInclusive<isize>>,
+    /// The directions a grain tries to fall, in order. Every direction goes down exactly one
+    /// row, so a grain can't skip the floor or a row of rock.
+    fall_directions: Vec<(isize, isize)>,
+}
+
+impl SandConfig {
+    /// The sand of part 2, with an infinite floor.
+    fn with_floor() -> Self {
+        SandConfig {
+            floor_offset: Some(FLOOR_OFFSET),
+            ..SandConfig::default()
+        }
+    }
+
+    /// Fail with the reason if the board can't be simulated with the config.
+    fn check(&self) -> Result<(), String> {
+        if let Some(offset) = self.floor_offset.filter(|offset| *offset < 1) {
+            return Err(format!("invalid floor {offset}, the floor has to be at least 1 row below the lowest rock"));
+        }
+
+        if let Some((dx, dy)) = self.fall_directions.iter().find(|(_, dy)| *dy != 1) {
+            return Err(format!("invalid fall direction {dx},{dy}, every direction has to go down one row"));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for SandConfig {
+    fn default() -> Self {
+        SandConfig {
+            spawns: vec![Position::new(500, 0)],
+            floor_offset: None,
+            floor_columns: None,
+            fall_directions: vec![(0, 1), (-1, 1), (1, 1)],
+        }
+    }
+}
+
+/// Run the simulation and redraw the board after every settled grain (or every step of a
+/// falling grain), waiting the delay between the frames.
+fn play(board: &mut Board, every_step: bool, delay: Duration, rows: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    // Clear the screen once, every frame then just moves the cursor to the top left
+    write!(out, "\x1b[2J")?;
+
+    loop {
+        let step = board.step();
+
+        if every_step || !matches!(step, Step::Fell(_)) {
+            let viewport = board.viewport_following(rows);
+            write!(out, "\x1b[H{}\x1b[J\n{} grains settled\n", board.render(viewport), board.settled)?;
+            out.flush()?;
+            sleep(delay);
+        }
+
+        if step == Step::Finished {
+            return Ok(());
+        }
+    }
+}
+
+/// What happened in one step of the simulation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Step {
+    /// The falling grain moved to the position.
+    Fell(Position),
+    /// The falling grain came to rest at the position.
+    Settled(Position),
+    /// Sand falls into the abyss or the spawn is blocked.
+    Finished,
 }
 
 struct Board {
-    width: isize,
-    height: isize,
-    fields: HashMap<Position, Field>,
+    /// Rock and sand, the top left cell is at `origin`.
+    fields: Grid<Option<Field>>,
+    origin: Position,
+    config: SandConfig,
+    /// The row of the floor, None if there is none.
+    floor: Option<isize>,
+    lowest_rock: isize,
+    /// The fall path of the current grain of every spawn, starting at the spawn. The next grain
+    /// takes the same path up to the position before the last grain settled, so it continues
+    /// from there.
+    paths: Vec<Vec<Position>>,
+    /// The index of the spawn whose grain falls next.
+    current: usize,
+    falling: bool,
+    /// The columns containing rock or sand, shown in the viewport.
+    columns: RangeInclusive<isize>,
+    /// The position of the last step, followed by the viewport.
+    last: Position,
+    settled: usize,
+    finished: bool,
 }
 
 impl Board {
+    /// Panics if the config is invalid, see `SandConfig::check`.
+    fn new(rocks: &HashSet<Position>, config: SandConfig) -> Self {
+        if let Err(reason) = config.check() {
+            panic!("invalid sand config: {reason}")
+        }
+
+        let spawn_ys = config.spawns.iter().map(|spawn| spawn.y);
+        let lowest_rock = rocks.iter().map(|pos| pos.y).max().or(spawn_ys.clone().max()).unwrap_or(0);
+        let floor = config.floor_offset.map(|offset| lowest_rock + offset);
+
+        // A grain moves one row down and at most the largest step sideways, so it stays in a triangle
+        // below its spawn
+        let top = rocks.iter().map(|pos| pos.y).chain(spawn_ys.clone()).min().unwrap_or(0);
+        let bottom = floor.map(|floor| floor - 1).unwrap_or(lowest_rock).max(spawn_ys.max().unwrap_or(0));
+        let step = config.fall_directions.iter().map(|(dx, _)| dx.abs()).max().unwrap_or(0);
+        let spread = |spawn: &Position| (bottom - spawn.y) * step;
+        let left = rocks.iter().map(|pos| pos.x).chain(config.spawns.iter().map(|spawn| spawn.x - spread(spawn))).min().unwrap_or(0);
+        let right = rocks.iter().map(|pos| pos.x).chain(config.spawns.iter().map(|spawn| spawn.x + spread(spawn))).max().unwrap_or(0);
+
+        let origin = Position::new(left, top);
+        let mut fields = Grid::new((right - left + 1) as usize, (bottom - top + 1) as usize, None);
+
+        for rock in rocks {
+            fields[Position::new(rock.x - origin.x, rock.y - origin.y)] = Some(Rock);
+        }
+
+        let columns = rocks.iter().map(|pos| pos.x).chain(config.spawns.iter().map(|spawn| spawn.x));
+        let columns = columns.clone().min().unwrap_or(0)..=columns.max().unwrap_or(0);
+
+        Board {
+            fields,
+            origin,
+            floor,
+            lowest_rock,
+            paths: vec![vec![]; config.spawns.len()],
+            current: 0,
+            falling: false,
+            columns,
+            last: config.spawns.first().copied().unwrap_or(origin),
+            settled: 0,
+            finished: false,
+            config,
+        }
+    }
+
     fn run_sand(&mut self) -> usize {
-        let mut sand_pos = self.sand_spawn();
-        let mut fallen_sand = 0;
-
-        loop {
-            match self.get_next_position(sand_pos) {
-                Fallen(new_pos) => sand_pos = new_pos,
-                Calm => {
-                    fallen_sand += 1;
-                    self.fields.insert(sand_pos, Sand);
-
-                    match self.fields.get(&self.sand_spawn()) {
-                        Some(_) => return fallen_sand,
-                        None => sand_pos = self.sand_spawn()
-                    }
-                }
-                FallToBottom => return fallen_sand
+        while self.step() != Step::Finished {}
+        self.settled
+    }
+
+    /// Move the falling grain by one position, spawning a new one if none is falling.
+    fn step(&mut self) -> Step {
+        if self.finished {
+            return Step::Finished;
+        }
+
+        let Some(sand_pos) = self.next_grain() else {
+            return self.finish();
+        };
+
+        match self.next_position(sand_pos) {
+            Some(new_pos) if self.falls_forever(new_pos) => self.finish(),
+            Some(new_pos) => {
+                self.paths[self.current].push(new_pos);
+                self.falling = true;
+                self.last = new_pos;
+                Step::Fell(new_pos)
+            }
+            None => {
+                self.settle(sand_pos);
+                Step::Settled(sand_pos)
             }
         }
     }
 
-    fn sand_spawn(&self) -> Position {
-        Position::new(500, 0)
-    }
+    /// The position of the falling grain. Spawns a new grain at the next free spawn if needed,
+    /// None if all spawns are blocked.
+    fn next_grain(&mut self) -> Option<Position> {
+        for _ in 0..self.config.spawns.len() {
+            if let Some(pos) = self.paths[self.current].last() {
+                return Some(*pos);
+            }
+
+            let spawn = self.config.spawns[self.current];
 
-    fn get_next_position(&self, sand_pos: Position) -> SandUpdate {
-        // A bit confusing. 'Up' means 'to the higher y pos'. Maybe I should rename this to something more general.
-        let max_y = self.height - 1;
-        let pos_down = sand_pos.position_in_direction(Up);
+            if self.is_free(spawn) {
+                self.paths[self.current].push(spawn);
+                return Some(spawn);
+            }
 
-        if let None = self.fields.get(&pos_down) {
-            return match pos_down.y == max_y {
-                true => FallToBottom,
-                false => Fallen(pos_down)
-            };
+            self.current = (self.current + 1) % self.config.spawns.len();
         }
 
-        let pos_down_left = sand_pos.position_in_direction(Up).position_in_direction(Left);
+        None
+    }
+
+    fn settle(&mut self, sand_pos: Position) {
+        let cell = self.cell(sand_pos);
+        self.fields[cell] = Some(Sand);
+
+        self.paths[self.current].pop();
 
-        if let None = self.fields.get(&pos_down_left) {
-            return match pos_down_left.y == max_y {
-                true => FallToBottom,
-                false => Fallen(pos_down_left)
-            };
+        // The grains of the other spawns can't pass the new sand anymore, so they continue
+        // before it
+        for path in &mut self.paths {
+            if let Some(i) = path.iter().position(|pos| *pos == sand_pos) {
+                path.truncate(i)
+            }
         }
 
-        let pos_down_right = sand_pos.position_in_direction(Up).position_in_direction(Right);
+        self.current = (self.current + 1) % self.config.spawns.len();
+        self.columns = *self.columns.start().min(&sand_pos.x)..=*self.columns.end().max(&sand_pos.x);
+        self.falling = false;
+        self.last = sand_pos;
+        self.settled += 1;
+    }
+
+    fn finish(&mut self) -> Step {
+        self.falling = false;
+        self.finished = true;
+        Step::Finished
+    }
+
+    /// The first free position the grain can fall to, None if it comes to rest.
+    fn next_position(&self, sand_pos: Position) -> Option<Position> {
+        self.config.fall_directions
+            .iter()
+            .map(|(dx, dy)| Position::new(sand_pos.x + dx, sand_pos.y + dy))
+            .find(|pos| self.is_free(*pos))
+    }
 
-        if let None = self.fields.get(&pos_down_right) {
-            return match pos_down_right.y == max_y {
-                true => FallToBottom,
-                false => Fallen(pos_down_right)
-            };
+    /// If nothing can stop the grain at the position anymore.
+    fn falls_forever(&self, pos: Position) -> bool {
+        match self.floor {
+            Some(floor) => pos.y >= floor,
+            None => pos.y >= self.lowest_rock
         }
+    }
 
-        Calm
+    fn is_free(&self, pos: Position) -> bool {
+        !self.is_floor(pos) && self.fields.get(self.cell(pos)).is_none_or(|field| field.is_none())
     }
 
-    fn add_floor(&mut self) {
-        self.height += 2;
-        let y = self.height - 1;
-        Position::new(0, y).iter_to(Position::new(1000, y)).for_each(|pos| { self.fields.insert(pos, Rock); });
+    fn is_floor(&self, pos: Position) -> bool {
+        Some(pos.y) == self.floor && self.config.floor_columns.as_ref().is_none_or(|columns| columns.contains(&pos.x))
     }
-}
 
-enum SandUpdate {
-    Fallen(Position),
-    Calm,
-    FallToBottom,
+    /// The position in the grid, which starts at the origin.
+    fn cell(&self, pos: Position) -> Position {
+        Position::new(pos.x - self.origin.x, pos.y - self.origin.y)
+    }
+
+    fn field(&self, pos: Position) -> Option<Field> {
+        match self.is_floor(pos) {
+            true => Some(Rock),
+            false => self.fields.get(self.cell(pos)).copied().flatten()
+        }
+    }
 }
 
-impl From<&str> for Board {
-    fn from(input: &str) -> Self {
-        let mut fields = HashMap::with_capacity(1000 * 200);
+/// The puzzle input: all positions covered by rock.
+fn parse_rocks(input: &str) -> Result<HashSet<Position>, ParseError> {
+    let mut rocks = HashSet::new();
 
-        input
-            .lines()
-            .map(Rocks::from)
-            .for_each(|rock_positions| rock_positions.rocks.into_iter().for_each(|pos| { fields.insert(pos, Rock); }));
+    for line in input.lines() {
+        rocks.extend(line.parse::<Rocks>()?.rocks);
+    }
 
-        let min_x = fields.keys().map(|pos| pos.x).min().unwrap();
-        let max_x = fields.keys().map(|pos| pos.x).max().unwrap();
-        let width = max_x - min_x + 1;
-        let height = fields.keys().map(|pos| pos.y).max().unwrap() + 1;
+    match rocks.is_empty() {
+        true => Err(ParseError::missing(input, "at least one rock path")),
+        false => Ok(rocks)
+    }
+}
 
-        Board {
-            width,
-            height,
-            fields,
-        }
+impl Board {
+    /// All columns containing rock or sand and the given amount of rows around the last step,
+    /// so the view follows the sand.
+    fn viewport_following(&self, rows: usize) -> (RangeInclusive<isize>, RangeInclusive<isize>) {
+        let (xs, ys) = self.bounds();
+        let (top, bottom) = (*ys.start(), *ys.end());
+        let rows = (rows.max(1) as isize).min(bottom - top + 1);
+        let first = (self.last.y - rows / 2).clamp(top, bottom - rows + 1);
+
+        (xs, first..=first + rows - 1)
+    }
+
+    /// The columns containing rock or sand and all rows down to the floor.
+    fn bounds(&self) -> (RangeInclusive<isize>, RangeInclusive<isize>) {
+        let bottom = self.floor.unwrap_or(self.lowest_rock);
+        (self.columns.clone(), self.origin.y..=bottom)
+    }
+
+    /// Draw the area like the puzzle description: rock as '#', sand as 'o' and the spawn as '+'.
+    fn render(&self, (xs, ys): (RangeInclusive<isize>, RangeInclusive<isize>)) -> String {
+        let falling = self.paths.get(self.current).and_then(|path| path.last()).filter(|_| self.falling);
+
+        ys
+            .map(|y| xs.clone()
+                .map(|x| Position::new(x, y))
+                .map(|pos| match self.field(pos) {
+                    Some(Rock) => '#',
+                    Some(Sand) => 'o',
+                    None if falling == Some(&pos) => 'o',
+                    None if self.config.spawns.contains(&pos) => '+',
+                    None => '.'
+                })
+                .collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 }
 
 impl Display for Board {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut res = String::new();
-
-        let min_x = self.fields.keys().map(|pos| pos.x).min().unwrap();
-
-        for y in 0..self.height {
-            for x in min_x..(min_x + self.width) {
-                res.push_str(match self.fields.get(&Position::from((x, y))) {
-                    Some(f) => match f {
-                        Rock => "#",
-                        Sand => "o",
-                    },
-                    None => "."
-                })
-            }
-
-            res.push_str("\n");
-        }
-
-        write!(f, "{res}")
+        write!(f, "{}", self.render(self.bounds()))
     }
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 enum Field {
     Rock,
     Sand,
@@ -152,23 +442,31 @@ struct Rocks {
     rocks: HashSet<Position>,
 }
 
-impl From<&str> for Rocks {
-    fn from(line: &str) -> Self {
+impl FromStr for Rocks {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
         let positions = line.split(" -> ")
             .map(|pos_str| {
-                let mut split = pos_str.split(",");
-                Position::new(
-                    split.next().unwrap().parse::<isize>().unwrap(),
-                    split.next().unwrap().parse::<isize>().unwrap(),
-                )
+                let mut split = pos_str.split(',');
+                let pos = Position::new(
+                    parse_number(next_part(&mut split, pos_str, "an x coordinate")?)?,
+                    parse_number(next_part(&mut split, pos_str, "a y coordinate")?)?,
+                );
+                Ok((pos_str, pos))
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, ParseError>>()?;
 
-        Rocks {
-            rocks: (0..positions.len() - 1)
-                .flat_map(|i| create_positions_between(positions[i], positions[i + 1]))
-                .collect()
+        if let Some(w) = positions.windows(2).find(|w| w[0].1.x != w[1].1.x && w[0].1.y != w[1].1.y) {
+            return Err(ParseError::new(w[1].0, "rock paths must be horizontal or vertical"));
         }
+
+        Ok(Rocks {
+            rocks: positions
+                .windows(2)
+                .flat_map(|w| create_positions_between(w[0].1, w[1].1))
+                .collect()
+        })
     }
 }
 
@@ -187,7 +485,11 @@ fn create_positions_between(a: Position, b: Position) -> PositionIter {
 
 #[cfg(test)]
 mod tests {
-    use crate::p14::{Board, Rocks};
+    use std::time::Duration;
+    use pad::Position;
+    use crate::p14::{Board, P14, parse_pairs, parse_rocks, play, Rocks, SandConfig, Step};
+    use crate::show::ShowOptions;
+    use crate::solver::Solver;
 
     #[test]
     fn rocks_from_line_works() {
@@ -195,7 +497,7 @@ mod tests {
             "490,63 -> 495,63",
             "498,4 -> 498,6 -> 496,6"
         ].into_iter().for_each(|line| {
-            let rocks = Rocks::from(line);
+            let rocks = line.parse::<Rocks>().unwrap();
             println!("{:?}", rocks.rocks)
         })
     }
@@ -205,11 +507,121 @@ mod tests {
         let input = "498,4 -> 498,6 -> 496,6
 503,4 -> 502,4 -> 502,9 -> 494,9";
 
-        let mut board = Board::from(input);
+        let rocks = parse_rocks(input).unwrap();
+
+        let mut board = Board::new(&rocks, SandConfig::default());
         assert_eq!(board.run_sand(), 24);
 
-        let mut board = Board::from(input);
-        board.add_floor();
+        let mut board = Board::new(&rocks, SandConfig::with_floor());
         assert_eq!(board.run_sand(), 93);
     }
+
+    #[test]
+    fn spawn_and_floor_work() {
+        let input = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
+        let rocks = parse_rocks(input).unwrap();
+
+        [
+            (Position::new(497, 0), Some(2), 79),
+            (Position::new(500, 0), Some(1), 79),
+            (Position::new(500, 0), Some(5), 159),
+            (Position::new(502, 2), None, 19),
+            (Position::new(496, 0), None, 1),
+        ].into_iter().for_each(|(spawn, floor_offset, expected)| {
+            let config = SandConfig { spawns: vec![spawn], floor_offset, ..SandConfig::default() };
+            assert_eq!(Board::new(&rocks, config).run_sand(), expected)
+        })
+    }
+
+    #[test]
+    fn sand_config_works() {
+        let input = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
+        let rocks = parse_rocks(input).unwrap();
+        let spawns = |spawns: &str| parse_pairs(spawns, "spawns").unwrap().into_iter().map(|(x, y)| Position::new(x, y)).collect();
+
+        [
+            (SandConfig { spawns: spawns("500,0:497,0"), ..SandConfig::with_floor() }, 124),
+            (SandConfig { spawns: spawns("500,0:510,0:490,0"), floor_offset: Some(4), ..SandConfig::default() }, 343),
+            // The second grain falls past the rocks
+            (SandConfig { spawns: spawns("500,0:503,0"), ..SandConfig::default() }, 1),
+            (SandConfig { floor_columns: Some(495..=505), ..SandConfig::with_floor() }, 24),
+            (SandConfig { fall_directions: parse_pairs("0,1:1,1:-1,1", "fall").unwrap(), ..SandConfig::with_floor() }, 93),
+            (SandConfig { floor_offset: Some(3), fall_directions: parse_pairs("0,1:-2,1:2,1", "fall").unwrap(), ..SandConfig::default() }, 126),
+        ].into_iter().for_each(|(config, expected)| {
+            assert_eq!(Board::new(&rocks, config).run_sand(), expected)
+        })
+    }
+
+    #[test]
+    fn invalid_sand_config_is_rejected() {
+        assert!(SandConfig::with_floor().check().is_ok());
+        assert_eq!(
+            SandConfig { floor_offset: Some(0), ..SandConfig::default() }.check().unwrap_err(),
+            "invalid floor 0, the floor has to be at least 1 row below the lowest rock"
+        );
+
+        let input = "498,4 -> 498,6 -> 496,6";
+        let options = ShowOptions::new(vec![("floor".to_string(), "-1".to_string())]);
+        let error = P14.show(input, &options, &mut vec![]).unwrap_err();
+        assert_eq!(error.to_string(), "invalid floor -1, the floor has to be at least 1 row below the lowest rock");
+
+        let options = ShowOptions::new(vec![("fall".to_string(), "0,1:0,2".to_string())]);
+        let error = P14.show(input, &options, &mut vec![]).unwrap_err();
+        assert_eq!(error.to_string(), "invalid fall direction 0,2, every direction has to go down one row");
+    }
+
+    #[test]
+    fn step_works() {
+        let input = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
+        let mut board = Board::new(&parse_rocks(input).unwrap(), SandConfig::default());
+
+        // The first grain falls straight down to the rock at y 9
+        for y in 1..=8 {
+            assert_eq!(board.step(), Step::Fell(Position::new(500, y)));
+        }
+
+        assert_eq!(board.step(), Step::Settled(Position::new(500, 8)));
+
+        // The next grain continues from the position before, instead of the spawn
+        assert_eq!(board.step(), Step::Fell(Position::new(499, 8)));
+
+        assert_eq!(board.to_string(), "......+...
+..........
+..........
+..........
+....#...##
+....#...#.
+..###...#.
+........#.
+.....oo.#.
+#########.");
+    }
+
+    #[test]
+    fn play_works() {
+        let input = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
+        let mut board = Board::new(&parse_rocks(input).unwrap(), SandConfig::default());
+        let mut out = vec![];
+        play(&mut board, false, Duration::ZERO, 3, &mut out).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let frames = out.split("\x1b[H").skip(1).collect::<Vec<_>>();
+
+        // One frame per settled grain and the final one
+        assert_eq!(frames.len(), 25);
+        // The last grain falls into the abyss, the viewport follows it down
+        assert_eq!(frames[24], "....oooo#.
+.o.ooooo#.
+#########.\x1b[J
+24 grains settled
+");
+    }
 }
@@ -1,74 +1,146 @@
-use std::convert::identity;
-use pad::Direction::{Down, Left, Right, Up};
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
 use pad::Position;
-use crate::input_reader::read_input;
+use crate::parse::{parse_number, ParseError};
+use crate::show::{ShowError, ShowOptions};
+use crate::solver::{Answer, Solver};
 
-pub fn solve_p15() {
-    let input = read_input(15);
-    let board = Board::from(input.as_str());
+pub struct P15;
 
-    let count_occupied_positions_at_row = board.count_occupied_positions_at_row(2000000);
-    println!("Solution 1: {count_occupied_positions_at_row}");
+impl Solver for P15 {
+    fn day(&self) -> usize {
+        15
+    }
+
+    fn title(&self) -> &'static str {
+        "Beacon Exclusion Zone"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        Board::try_from(input).map(drop)
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let count_occupied_positions_at_row = Board::try_from(input)?.count_occupied_positions_at_row(2000000);
+        Ok(count_occupied_positions_at_row.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let tuning_frequency = Board::try_from(input)?.determine_tuning_frequency(4000000);
+        Ok(tuning_frequency.into())
+    }
+
+    /// Prints the map of the sensors like the puzzle description. The viewport is set with
+    /// `x=MIN..=MAX` and `y=MIN..=MAX`, `bound=N` marks the distress beacon in 0..=N.
+    fn show(&self, input: &str, options: &ShowOptions, out: &mut dyn Write) -> Result<(), ShowError> {
+        options.check_known(&["x", "y", "bound"])?;
+        let mut board = Board::try_from(input)?;
 
-    let tuning_frequency = board.determine_tuning_frequency(4000000);
-    println!("Solution 2: {tuning_frequency}");
+        let (x, y) = board.viewport.clone();
+        let (x, y) = (options.get_range_or("x", x)?, options.get_range_or("y", y)?);
+        let cells = [&x, &y]
+            .map(|range| (range.end() - range.start() + 1).max(0) as usize)
+            .into_iter()
+            .fold(1, usize::saturating_mul);
+
+        if cells > MAX_VIEWPORT_CELLS {
+            return Err(ShowError::Option(format!(
+                "the viewport {x:?}, {y:?} has {cells} cells, choose at most {MAX_VIEWPORT_CELLS} with x=MIN..=MAX and y=MIN..=MAX"
+            )));
+        }
+
+        board.viewport = (x, y);
+
+        if let Some(bound) = options.get("bound") {
+            let bound = bound.parse().map_err(|_| ShowError::Option(format!("invalid bound '{bound}'")))?;
+            board.distress_beacon = board.find_distress_beacon(bound);
+        }
+
+        writeln!(out, "{board}")?;
+        Ok(())
+    }
 }
 
+/// The most cells `show` draws. The real input spans millions of positions in both directions.
+const MAX_VIEWPORT_CELLS: usize = 1_000_000;
+
 struct Board {
     sensors_beacons: Vec<SensorBeacon>,
+    /// The x and y ranges shown by Display, by default the area of all sensors and beacons.
+    viewport: (RangeInclusive<isize>, RangeInclusive<isize>),
+    /// Shown by Display if set.
+    distress_beacon: Option<Position>,
 }
 
 impl Board {
     fn count_occupied_positions_at_row(&self, row: isize) -> usize {
-        let start = Position::new(self.min_x(), row);
-        let end = Position::new(self.max_x(), row);
+        let intervals = self.covered_intervals(row);
+        let covered = intervals.iter().map(|interval| interval.end() - interval.start() + 1).sum::<isize>() as usize;
 
-        start.iter_to(end)
-            .into_iter()
-            .filter(|pos| self.sensors_beacons.iter().all(|sb| sb.sensor != *pos && sb.beacon != *pos))
-            .filter(|pos| self.sensors_beacons.iter().any(|sb| manhattan_distance(*pos, sb.sensor) <= sb.range))
-            .count()
+        // Sensors and beacons are always inside the coverage of their own sensor
+        let mut occupied = self.sensors_beacons
+            .iter()
+            .flat_map(|sb| [sb.sensor, sb.beacon])
+            .filter(|pos| pos.y == row)
+            .collect::<Vec<_>>();
+        occupied.sort();
+        occupied.dedup();
+
+        covered - occupied.len()
     }
 
-    /// Disclaimer: I needed a hint from reddit to solve part 2. Thanks to
-    /// https://www.reddit.com/r/adventofcode/comments/zmfwg1/2022_day_15_part_2_seekin_for_the_beacon/
-    fn determine_tuning_frequency(&self, bound: isize) -> u128 {
-        for i in 0..self.sensors_beacons.len() {
-            let res = self.sensors_beacons[i]
-                .perimeters()
-                .into_iter()
-                .filter(|pos| pos.x >= 0 && pos.x <= bound && pos.y >= 0 && pos.y <= bound)
-                .filter(|pos| self.sensors_beacons
-                    .iter()
-                    .enumerate()
-                    .filter(|(j, _)| *j != i)
-                    .all(|(_, sb)| manhattan_distance(*pos, sb.sensor) > sb.range))
-                .next();
-
-            if let Some(res) = res {
-                return res.x as u128 * 4000000 + res.y as u128
+    /// The x ranges of the row covered by at least one sensor, sorted and merged,
+    /// so they neither overlap nor touch each other.
+    pub fn covered_intervals(&self, row: isize) -> Vec<RangeInclusive<isize>> {
+        let mut intervals = self.sensors_beacons
+            .iter()
+            .filter_map(|sb| sb.covered_at_row(row))
+            .collect::<Vec<_>>();
+        intervals.sort_by_key(|interval| *interval.start());
+
+        let mut merged: Vec<RangeInclusive<isize>> = Vec::with_capacity(intervals.len());
+
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if *interval.start() <= last.end() + 1 => {
+                    if interval.end() > last.end() {
+                        *last = *last.start()..=*interval.end()
+                    }
+                }
+                _ => merged.push(interval)
             }
         }
 
-        panic!("no solution")
+        merged
     }
 
-    fn min_x(&self) -> isize {
-        self.sensors_beacons
-            .iter()
-            .flat_map(|sb| [(sb, sb.sensor.x), (sb, sb.beacon.x)])
-            .map(|(sb, x)| x - sb.range as isize)
-            .min()
-            .unwrap_or(0)
+    fn determine_tuning_frequency(&self, bound: isize) -> u128 {
+        let beacon = self.find_distress_beacon(bound).expect("no solution");
+        beacon.x as u128 * 4000000 + beacon.y as u128
     }
 
-    fn max_x(&self) -> isize {
-        self.sensors_beacons
-            .iter()
-            .flat_map(|sb| [(sb, sb.sensor.x), (sb, sb.beacon.x)])
-            .map(|(sb, x)| x + sb.range as isize)
-            .max()
-            .unwrap_or(0)
+    /// Find the only position in 0..=bound for x and y that is not covered by any sensor,
+    /// by looking for a gap in the covered intervals of every row.
+    fn find_distress_beacon(&self, bound: isize) -> Option<Position> {
+        for row in 0..=bound {
+            let mut x = 0;
+
+            for interval in self.covered_intervals(row) {
+                if *interval.start() > x {
+                    break;
+                }
+
+                x = x.max(interval.end() + 1);
+            }
+
+            if x <= bound {
+                return Some(Position::new(x, row))
+            }
+        }
+
+        None
     }
 }
 
@@ -80,79 +152,132 @@ struct SensorBeacon {
 }
 
 impl SensorBeacon {
-    fn perimeters<'a>(&'a self) -> impl IntoIterator<Item=Position> + 'a {
-        let sensor_x = self.sensor.x;
-        let sensor_y = self.sensor.y;
-        let min_y = self.sensor.y - self.range as isize;
-        let max_y = self.sensor.y + self.range as isize;
-
-        (min_y..=max_y).into_iter()
-            .flat_map(move |y| if y == min_y {
-                let pos = Position::new(sensor_x, y);
-                [
-                    Some(pos.position_in_direction(Down)),
-                    Some(pos.position_in_direction(Left)),
-                    Some(pos.position_in_direction(Right))
-                ]
-            } else if y == max_y {
-                let pos = Position::new(sensor_x, y);
-                [
-                    Some(pos.position_in_direction(Up)),
-                    Some(pos.position_in_direction(Left)),
-                    Some(pos.position_in_direction(Right))
-                ]
-            } else {
-                let diff = (self.range - sensor_y.abs_diff(y)) as isize + 1;
-                let left = Position::new(sensor_x - diff, y);
-                let right = Position::new(sensor_x + diff, y);
-
-                [Some(left), Some(right), None]
-            })
-            .flat_map(identity)
+    /// The x range of the row within the range of the sensor, if it reaches the row at all.
+    fn covered_at_row(&self, row: isize) -> Option<RangeInclusive<isize>> {
+        let reach = self.range.checked_sub(self.sensor.y.abs_diff(row))? as isize;
+        Some(self.sensor.x - reach..=self.sensor.x + reach)
     }
 }
 
-impl From<&str> for Board {
-    fn from(input: &str) -> Self {
-        Board {
-            sensors_beacons: input.lines().map(SensorBeacon::from).collect()
+impl TryFrom<&str> for Board {
+    type Error = ParseError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let sensors_beacons = input.lines().map(SensorBeacon::from_str).collect::<Result<Vec<_>, _>>()?;
+
+        let positions = || sensors_beacons.iter().flat_map(|sb| [sb.sensor, sb.beacon]);
+        let x = positions().map(|pos| pos.x).min().unwrap_or(0)..=positions().map(|pos| pos.x).max().unwrap_or(0);
+        let y = positions().map(|pos| pos.y).min().unwrap_or(0)..=positions().map(|pos| pos.y).max().unwrap_or(0);
+
+        Ok(Board {
+            sensors_beacons,
+            viewport: (x, y),
+            distress_beacon: None,
+        })
+    }
+}
+
+/// Draws the viewport with axis labels like the puzzle description: sensors as 'S', beacons
+/// as 'B', the distress beacon as 'D', covered positions as '#' and all others as '.'.
+impl Display for Board {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (xs, ys) = &self.viewport;
+        let label_width = ys.clone().map(|y| y.to_string().len()).max().unwrap_or(0);
+
+        // Every fifth column is labeled, with the digits written from top to bottom
+        let column_labels = xs.clone()
+            .map(|x| match x % 5 {
+                0 => x.to_string(),
+                _ => String::new()
+            })
+            .collect::<Vec<_>>();
+        let label_height = column_labels.iter().map(String::len).max().unwrap_or(0);
+
+        for i in 0..label_height {
+            let digits = column_labels
+                .iter()
+                .map(|label| {
+                    let padding = label_height - label.len();
+                    match i < padding {
+                        true => ' ',
+                        false => label.as_bytes()[i - padding] as char
+                    }
+                })
+                .collect::<String>();
+
+            writeln!(f, "{:label_width$} {}", "", digits.trim_end())?;
         }
+
+        for y in ys.clone() {
+            let intervals = self.covered_intervals(y);
+
+            let row = xs.clone()
+                .map(|x| Position::new(x, y))
+                .map(|pos| match pos {
+                    _ if self.sensors_beacons.iter().any(|sb| sb.sensor == pos) => 'S',
+                    _ if self.sensors_beacons.iter().any(|sb| sb.beacon == pos) => 'B',
+                    _ if self.distress_beacon == Some(pos) => 'D',
+                    _ if intervals.iter().any(|interval| interval.contains(&pos.x)) => '#',
+                    _ => '.'
+                })
+                .collect::<String>();
+
+            write!(f, "{y:>label_width$} {row}")?;
+
+            if y != *ys.end() {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
     }
 }
 
-impl From<&str> for SensorBeacon {
-    fn from(line: &str) -> Self {
-        let cleaned = line.replace("Sensor at ", "")
-            .replace(": closest beacon is at ", "|")
-            .replace("x=", "")
-            .replace(", y=", "|");
-        let mut split = cleaned.split("|");
+impl FromStr for SensorBeacon {
+    type Err = ParseError;
 
-        let mut next_val = || split.next().unwrap().parse::<isize>().unwrap();
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let rest = line.strip_prefix("Sensor at ").ok_or_else(|| ParseError::new(line, "expected 'Sensor at '"))?;
+        let (sensor, beacon) = rest
+            .split_once(": closest beacon is at ")
+            .ok_or_else(|| ParseError::new(rest, "expected ': closest beacon is at '"))?;
 
-        let sensor = Position::new(next_val(), next_val());
-        let beacon = Position::new(next_val(), next_val());
+        let sensor = parse_position(sensor)?;
+        let beacon = parse_position(beacon)?;
 
-        SensorBeacon {
+        Ok(SensorBeacon {
             sensor,
             beacon,
             range: manhattan_distance(sensor, beacon),
-        }
+        })
     }
 }
 
+/// Parse a position like 'x=2, y=18'.
+fn parse_position(s: &str) -> Result<Position, ParseError> {
+    let (x, y) = s
+        .strip_prefix("x=")
+        .and_then(|rest| rest.split_once(", y="))
+        .ok_or_else(|| ParseError::new(s, "expected a position like 'x=2, y=18'"))?;
+
+    Ok(Position::new(parse_number(x)?, parse_number(y)?))
+}
+
 fn manhattan_distance(a: Position, b: Position) -> usize {
     a.x.abs_diff(b.x) + a.y.abs_diff(b.y)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::p15::{Board, SensorBeacon};
+    use pad::Position;
+    use crate::p15::{Board, P15, SensorBeacon};
+    use crate::show::ShowOptions;
+    use crate::solver::Solver;
 
     #[test]
     fn sensor_beacon_from_string_works() {
         let input = "Sensor at x=8, y=7: closest beacon is at x=2, y=10";
-        let sb = SensorBeacon::from(input);
+        let sb = input.parse::<SensorBeacon>().unwrap();
         println!("{:?}", sb);
     }
 
@@ -173,16 +298,67 @@ Sensor at x=16, y=7: closest beacon is at x=15, y=3
 Sensor at x=14, y=3: closest beacon is at x=15, y=3
 Sensor at x=20, y=1: closest beacon is at x=15, y=3";
 
-        let board = Board::from(input);
+        let board = Board::try_from(input).unwrap();
         assert_eq!(board.count_occupied_positions_at_row(10), 26);
         assert_eq!(board.determine_tuning_frequency(20), 56000011);
+        assert_eq!(board.find_distress_beacon(20), Some(Position::new(14, 11)));
+    }
+
+    #[test]
+    fn display_works() {
+        let input = "Sensor at x=2, y=2: closest beacon is at x=3, y=3";
+        let mut board = Board::try_from(input).unwrap();
+        assert_eq!(board.to_string(), "2 S#\n3 #B");
+
+        board.viewport = (0..=10, 0..=4);
+        board.distress_beacon = Some(Position::new(5, 4));
+
+        assert_eq!(board.to_string(), "            1
+  0    5    0
+0 ..#........
+1 .###.......
+2 ##S##......
+3 .##B.......
+4 ..#..D.....");
+    }
+
+    #[test]
+    fn large_viewports_are_refused() {
+        let input = "Sensor at x=0, y=0: closest beacon is at x=1, y=0
+Sensor at x=2000000, y=2000000: closest beacon is at x=2000001, y=2000000";
+
+        let error = P15.show(input, &ShowOptions::default(), &mut vec![]).unwrap_err();
+        assert_eq!(error.to_string(), "the viewport 0..=2000001, 0..=2000000 has 4000006000002 cells, choose at most 1000000 with x=MIN..=MAX and y=MIN..=MAX");
+
+        let options = ShowOptions::new(vec![("x".to_string(), "0..=3".to_string()), ("y".to_string(), "0..=1".to_string())]);
+        let mut out = vec![];
+        P15.show(input, &options, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "  0\n0 SB..\n1 #...\n");
+    }
+
+    #[test]
+    fn covered_intervals_work() {
+        let input = "Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+
+        let board = Board::try_from(input).unwrap();
+
+        // At row 7 the sensor at (8, 7) covers -1..=17, (2, 0) covers -1..=5 and (20, 1) 19..=21
+        assert_eq!(board.covered_intervals(7), vec![-1..=17, 19..=21]);
+        assert_eq!(board.covered_intervals(-5), vec![-3..=7, 19..=21]);
+        assert_eq!(board.covered_intervals(16), vec![8..=8]);
+        assert_eq!(board.covered_intervals(17), vec![]);
     }
 
     #[test]
-    fn get_perimeters_works() {
-        let input = "Sensor at x=0, y=0: closest beacon is at x=2, y=2";
-        let sb = SensorBeacon::from(input);
+    fn touching_intervals_are_merged() {
+        let input = "Sensor at x=0, y=0: closest beacon is at x=1, y=0
+Sensor at x=3, y=0: closest beacon is at x=4, y=0
+Sensor at x=10, y=0: closest beacon is at x=11, y=0";
 
-        sb.perimeters().into_iter().for_each(|pos| println!("{:?}", pos));
+        let board = Board::try_from(input).unwrap();
+        assert_eq!(board.covered_intervals(0), vec![-1..=4, 9..=11]);
+        assert_eq!(board.count_occupied_positions_at_row(0), 9 - 6);
     }
 }
\ No newline at end of file
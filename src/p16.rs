@@ -1,123 +1,251 @@
-use std::collections::{HashMap, HashSet};
-use crate::input_reader::read_input;
+use std::collections::HashMap;
+use std::io::Write;
+use std::str::FromStr;
+use crate::parse::{parse_number, ParseError};
+use crate::search::all_pairs;
+use crate::show::{ShowError, ShowOptions};
+use crate::solver::{Answer, Solver};
+
+pub struct P16;
+
+impl Solver for P16 {
+    fn day(&self) -> usize {
+        16
+    }
 
-pub fn solve_p16() {
-    let input = read_input(16);
-    let mut runner = Runner::from(input.as_str());
-    runner.run();
+    fn title(&self) -> &'static str {
+        "Proboscidea Volcanium"
+    }
 
-    // Output seems to be random
-    println!("Solution 1: {}", runner.flown);
-}
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        Volcano::try_from(input).map(drop)
+    }
 
-struct Runner {
-    current: String,
-    remaining_minutes: usize,
-    flown: usize,
-    valves: HashMap<String, Valve>,
-    graph: Graph,
-    opened: HashSet<Valve>,
-}
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let mut volcano = Volcano::try_from(input)?;
+        Ok(volcano.plan_alone(30).pressure.into())
+    }
 
-impl Runner {
-    fn run(&mut self) {
-        while self.remaining_minutes > 0 {
-            println!("Remaining {}", self.remaining_minutes);
-            let best_next = self.get_best_next();
-
-            match best_next {
-                Some((ident, len)) => {
-                    println!("move to {} with len {}", ident, len);
-                    self.move_to_best_and_open((ident, len))
-                },
-                None => {
-                    println!("idle");
-                    self.idle()
-                }
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let mut volcano = Volcano::try_from(input)?;
+        let plans = volcano.plan_with_elephant(26);
+        Ok(plans.iter().map(|plan| plan.pressure).sum::<usize>().into())
+    }
+
+    /// Lists the valves opened in the plan of the part (`part=1` alone, `part=2` with the
+    /// elephant), optionally with another time limit (`minutes=N`).
+    fn show(&self, input: &str, options: &ShowOptions, out: &mut dyn Write) -> Result<(), ShowError> {
+        options.check_known(&["part", "minutes"])?;
+        let mut volcano = Volcano::try_from(input)?;
+
+        let plans = match options.get_or("part", 1)? {
+            1 => vec![("you", volcano.plan_alone(options.get_or("minutes", 30)?))],
+            2 => {
+                let [mine, elephant] = volcano.plan_with_elephant(options.get_or("minutes", 26)?);
+                vec![("you", mine), ("elephant", elephant)]
+            }
+            part => return Err(ShowError::Option(format!("unknown part {part}")))
+        };
+
+        for (who, plan) in &plans {
+            writeln!(out, "{who}:")?;
+
+            for m in &plan.moves {
+                writeln!(out, "  minute {:>2}: open {}", m.minute, m.valve)?;
             }
         }
+
+        writeln!(out, "pressure released: {}", plans.iter().map(|(_, plan)| plan.pressure).sum::<usize>())?;
+        Ok(())
     }
+}
+
+/// The valves to open in the order they are opened. The minute is the one in which
+/// the valve gets opened, it releases pressure from the next minute on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Plan {
+    pub pressure: usize,
+    pub moves: Vec<Move>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Move {
+    pub valve: String,
+    pub minute: usize,
+}
+
+/// The tunnels compressed to the valves with a positive flow rate (plus the start valve)
+/// and the lengths of the shortest paths between them. Opened valves are stored as bitmask,
+/// where bit i is the valve at index i.
+struct Volcano {
+    names: Vec<String>,
+    flow_rates: Vec<usize>,
+    distances: Vec<Vec<usize>>,
+    /// Index of the start valve, which comes after all valves with positive flow.
+    start: usize,
+    /// Most pressure released from (position, remaining minutes, opened valves) on.
+    memo: HashMap<(usize, usize, u64), usize>,
+}
 
-    fn get_best_next(&self) -> Option<(String, usize)> {
-        let paths = self.valves
-            .values()
-            .filter(|valve| !self.opened.contains(valve))
-            .filter(|valve| valve.ident != self.current)
-            .map(|valve| (
-                valve.ident.clone(),
-                valve.flow_rate,
-                a_star(
-                    self.current.clone(),
-                    valve.ident.clone(),
-                    &self.graph,
-                )
-                    .map(|vec| vec.len() - 1)
-                    .unwrap_or(usize::MAX)))
+impl Volcano {
+    fn new(valves: Vec<Valve>, start: &str) -> Self {
+        let graph = Graph::new(valves.iter().cloned());
+
+        let relevant = valves
+            .iter()
+            .filter(|valve| valve.flow_rate > 0)
+            .collect::<Vec<_>>();
+        let names = relevant
+            .iter()
+            .map(|valve| valve.ident.clone())
+            .chain([start.to_string()])
             .collect::<Vec<_>>();
+        let flow_rates = relevant.iter().map(|valve| valve.flow_rate).chain([0]).collect();
+
+        let all_distances = all_pairs(&names, |valve| graph
+            .adjacent_edges(valve)
+            .into_iter()
+            .map(|edge| (edge.to.clone(), 1))
+            .collect::<Vec<_>>());
+        let distances = names
+            .iter()
+            .map(|from| names.iter().map(|to| all_distances[from].get(to).copied().unwrap_or(usize::MAX)).collect())
+            .collect();
+
+        Volcano {
+            start: names.len() - 1,
+            names,
+            flow_rates,
+            distances,
+            memo: HashMap::new(),
+        }
+    }
 
-        let mut best_nearest = None;
-
-        for i in 1..=self.valves.len() + 1 {
-            let nearest = paths
-                .iter()
-                .filter(|(_, _, len)| *len == i)
-                .filter(|(_, flow, _)| *flow > 0)
-                .map(|(ident, flow, len)| (ident, len, (*flow as f32 / *len as f32).ceil()))
-                .max_by(|(_, _, flow_a), (_, _, flow_b)| flow_a.partial_cmp(&flow_b).unwrap());
-
-            match nearest {
-                None => (),
-                Some((ident,len, flow)) => match best_nearest {
-                    None => best_nearest = Some((ident.clone(), len, flow)),
-                    Some((_,_, best_flow)) => if flow > best_flow {
-                        best_nearest = Some((ident.clone(), len, flow))
-                    }
+    fn plan_alone(&mut self, minutes: usize) -> Plan {
+        self.plan(minutes, 0)
+    }
+
+    /// Split the valves between you and the elephant, so both open only their own valves.
+    /// The best split is the pair of disjoint opened sets releasing the most pressure together.
+    fn plan_with_elephant(&mut self, minutes: usize) -> [Plan; 2] {
+        let all = (1u64 << self.start) - 1;
+
+        let mut best = self.best_per_opened(minutes).into_iter().collect::<Vec<_>>();
+        best.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        let mut split = (0, 0);
+        let mut most = 0;
+
+        for (i, (mine, my_pressure)) in best.iter().enumerate() {
+            // The pressures are sorted, so no later pair can release more
+            if my_pressure * 2 < most {
+                break;
+            }
+
+            if let Some((elephants, elephant_pressure)) = best[i..].iter().find(|(other, _)| mine & other == 0) {
+                if my_pressure + elephant_pressure > most {
+                    most = my_pressure + elephant_pressure;
+                    split = (*mine, *elephants);
                 }
             }
         }
 
-        let bes_nearest = best_nearest?;
-        Some((bes_nearest.0, *bes_nearest.1))
+        // Each gets all valves of the other as already opened, which releases at least the
+        // pressure of the split as every plan is the best one for its valves
+        [self.plan(minutes, !split.0 & all), self.plan(minutes, !split.1 & all)]
     }
 
-    fn move_to_best_and_open(&mut self, (ident, len): (String, usize)) {
-        self.flown += self.get_current_flow() * (len + 1); // +1 because i open the valve later
-        self.remaining_minutes = self.remaining_minutes.checked_sub(len).unwrap_or(0);
-        self.open_valve(&ident);
-        self.current = ident;
+    /// The most pressure released for every set of valves that can be opened in time.
+    fn best_per_opened(&self, minutes: usize) -> HashMap<u64, usize> {
+        let mut best = HashMap::new();
+        self.visit(self.start, minutes, 0, 0, &mut best);
+        best
     }
 
-    fn get_current_flow(&self) -> usize {
-        self.opened.iter().map(|valve| valve.flow_rate).sum()
+    fn visit(&self, position: usize, remaining: usize, opened: u64, pressure: usize, best: &mut HashMap<u64, usize>) {
+        let entry = best.entry(opened).or_insert(0);
+        *entry = pressure.max(*entry);
+
+        for (next, next_remaining) in self.next_valves(position, remaining, opened) {
+            let released = self.flow_rates[next] * next_remaining;
+            self.visit(next, next_remaining, opened | 1 << next, pressure + released, best);
+        }
     }
 
-    fn open_valve(&mut self, ident: &String) {
-        let valve = self.valves.get(ident).unwrap();
-        self.opened.insert(valve.clone());
-        self.remaining_minutes = self.remaining_minutes.checked_sub(1).unwrap_or(0);
+    /// The best plan if the valves in the opened mask are already open (or not available).
+    fn plan(&mut self, minutes: usize, mut opened: u64) -> Plan {
+        let pressure = self.max_pressure(self.start, minutes, opened);
+        let mut moves = vec![];
+        let mut position = self.start;
+        let mut remaining = minutes;
+        let mut left = pressure;
+
+        while left > 0 {
+            let (next, next_remaining) = self.next_valves(position, remaining, opened)
+                .collect::<Vec<_>>()
+                .into_iter()
+                .find(|(next, next_remaining)| {
+                    let released = self.flow_rates[*next] * next_remaining;
+                    released + self.max_pressure(*next, *next_remaining, opened | 1 << next) == left
+                })
+                .expect("the best pressure should be reachable");
+
+            left -= self.flow_rates[next] * next_remaining;
+            moves.push(Move { valve: self.names[next].clone(), minute: minutes - next_remaining });
+            position = next;
+            remaining = next_remaining;
+            opened |= 1 << next;
+        }
+
+        Plan { pressure, moves }
     }
 
-    fn idle(&mut self) {
-        self.flown += self.get_current_flow();
-        self.remaining_minutes -= 1;
+    fn max_pressure(&mut self, position: usize, remaining: usize, opened: u64) -> usize {
+        if let Some(pressure) = self.memo.get(&(position, remaining, opened)) {
+            return *pressure;
+        }
+
+        let pressure = self.next_valves(position, remaining, opened)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|(next, next_remaining)| {
+                self.flow_rates[next] * next_remaining + self.max_pressure(next, next_remaining, opened | 1 << next)
+            })
+            .max()
+            .unwrap_or(0);
+
+        self.memo.insert((position, remaining, opened), pressure);
+        pressure
+    }
+
+    /// The closed valves that can be reached and opened in time, together with the minutes
+    /// remaining after opening them.
+    fn next_valves(&self, position: usize, remaining: usize, opened: u64) -> impl Iterator<Item=(usize, usize)> + '_ {
+        (0..self.start)
+            .filter(move |next| opened & 1 << next == 0)
+            .filter_map(move |next| remaining
+                .checked_sub(self.distances[position][next].saturating_add(1))
+                .filter(|next_remaining| *next_remaining > 0)
+                .map(|next_remaining| (next, next_remaining)))
     }
 }
 
-impl From<&str> for Runner {
-    fn from(input: &str) -> Self {
-        let valves = input.lines().map(Valve::from).collect::<Vec<_>>();
-        let graph = Graph::new(valves.clone());
+impl TryFrom<&str> for Volcano {
+    type Error = ParseError;
 
-        let current = valves[0].ident.clone();
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let valves = input.lines().map(Valve::from_str).collect::<Result<Vec<_>, _>>()?;
 
-        Runner {
-            current,
-            remaining_minutes: 30,
-            flown: 0,
-            valves: valves.into_iter().map(|valve| (valve.ident.clone(), valve)).collect(),
-            graph,
-            opened: HashSet::new()
+        if !valves.iter().any(|valve| valve.ident == "AA") {
+            return Err(ParseError::missing(input, "the start valve AA"));
         }
+
+        // The opened valves are a bitmask, which has room for 63 of them besides the start
+        if let Some((line, _)) = input.lines().zip(&valves).filter(|(_, valve)| valve.flow_rate > 0).nth(63) {
+            return Err(ParseError::new(line, "more than 63 valves with positive flow rate"));
+        }
+
+        Ok(Volcano::new(valves, "AA"))
     }
 }
 
@@ -142,61 +270,10 @@ impl Graph {
         }
     }
 
-    fn adjacent_edges<'a>(&'a self, node: &'a String) -> impl IntoIterator<Item=&Edge> + 'a {
-        self.edges.iter().filter(move |edge| &edge.from == node)
+    fn adjacent_edges<'a>(&'a self, node: &'a str) -> impl IntoIterator<Item=&'a Edge> + 'a {
+        self.edges.iter().filter(move |edge| edge.from == node)
     }
-}
-
-// Thanks wikipedia
-fn a_star(start: String, goal: String, graph: &Graph) -> Option<Vec<String>> {
-    let mut open_set: HashSet<String> = HashSet::new();
-    open_set.insert(start.clone());
-
-    let mut came_from: HashMap<String, String> = HashMap::new();
-
-    let mut g_score: HashMap<String, usize> = HashMap::new();
-    g_score.insert(start.clone(), 0);
-
-    let mut f_score: HashMap<String, usize> = HashMap::new();
-    f_score.insert(start.clone(), 1);
-
-    while !open_set.is_empty() {
-        let current = open_set.iter().next().unwrap().clone();
 
-        if current == goal {
-            return Some(construct_path(came_from, current));
-        }
-
-        open_set.remove(&current);
-
-        for edge in graph.adjacent_edges(&current).into_iter() {
-            let to = &edge.to;
-            let tentative_g_score = g_score.get(&current).unwrap() + 1;
-
-            if tentative_g_score < *g_score.get(to).unwrap_or(&usize::MAX) {
-                came_from.insert(to.clone(), current.clone());
-                g_score.insert(to.clone(), tentative_g_score);
-                f_score.insert(to.clone(), tentative_g_score + 1);
-
-                if !open_set.contains(to) {
-                    open_set.insert(to.clone());
-                }
-            }
-        }
-    }
-
-    None
-}
-
-fn construct_path(came_from: HashMap<String, String>, mut current: String) -> Vec<String> {
-    let mut total_path = vec![current.clone()];
-
-    while came_from.contains_key(&current) {
-        current = came_from.get(&current).unwrap().clone();
-        total_path.push(current.clone());
-    }
-
-    total_path
 }
 
 struct Edge {
@@ -217,38 +294,35 @@ struct Valve {
     neighbours: Vec<String>,
 }
 
-impl From<&str> for Valve {
-    fn from(line: &str) -> Self {
-        let cleaned = line
-            .replace("Valve ", "")
-            .replace(" has flow rate=", "|")
-            .replace("; tunnels lead to valves ", "|")
-            .replace("; tunnel leads to valve ", "|");
-
-        let mut split = cleaned.split("|");
-
-        Valve {
-            ident: split.next().unwrap().to_string(),
-            flow_rate: split.next().unwrap().parse::<usize>().unwrap(),
-            neighbours: split.next().unwrap().split(",").map(|s| s.trim()).map(String::from).collect(),
-        }
+impl FromStr for Valve {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let rest = line.strip_prefix("Valve ").ok_or_else(|| ParseError::new(line, "expected 'Valve '"))?;
+        let (ident, rest) = rest
+            .split_once(" has flow rate=")
+            .ok_or_else(|| ParseError::new(rest, "expected ' has flow rate='"))?;
+        let (flow_rate, neighbours) = rest
+            .split_once("; tunnels lead to valves ")
+            .or_else(|| rest.split_once("; tunnel leads to valve "))
+            .ok_or_else(|| ParseError::new(rest, "expected the tunnels"))?;
+
+        Ok(Valve {
+            ident: ident.to_string(),
+            flow_rate: parse_number(flow_rate)?,
+            neighbours: neighbours.split(", ").map(String::from).collect(),
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::p16::{a_star, Graph, Runner, Valve};
-
-    #[test]
-    fn valve_from_str_works() {
-        let input = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB";
-        let valve = Valve::from(input);
-        println!("{:?}", valve)
-    }
+    use crate::p16::{Graph, Move, P16, Valve, Volcano};
+    use crate::search::bfs;
+    use crate::show::ShowOptions;
+    use crate::solver::Solver;
 
-    #[test]
-    fn graph_works() {
-        let input = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+    const EXAMPLE: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
 Valve BB has flow rate=13; tunnels lead to valves CC, AA
 Valve CC has flow rate=2; tunnels lead to valves DD, BB
 Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
@@ -259,28 +333,74 @@ Valve HH has flow rate=22; tunnel leads to valve GG
 Valve II has flow rate=0; tunnels lead to valves AA, JJ
 Valve JJ has flow rate=21; tunnel leads to valve II";
 
-        let graph = Graph::new(input.lines().map(Valve::from));
+    #[test]
+    fn valve_from_str_works() {
+        let input = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB";
+        let valve = input.parse::<Valve>().unwrap();
 
-        let path = a_star("DD".to_string(), "JJ".to_string(), &graph);
-        println!("{:?}", path)
+        assert_eq!(valve, Valve {
+            ident: "AA".to_string(),
+            flow_rate: 0,
+            neighbours: vec!["DD".to_string(), "II".to_string(), "BB".to_string()],
+        })
+    }
+
+    #[test]
+    fn graph_works() {
+        let graph = Graph::new(EXAMPLE.lines().map(|line| line.parse::<Valve>().unwrap()));
+        let search = bfs(["DD".to_string()], |valve| graph
+            .adjacent_edges(valve)
+            .into_iter()
+            .map(|edge| edge.to.clone())
+            .collect::<Vec<_>>());
+
+        assert_eq!(search.distance(&"JJ".to_string()), Some(3));
+        assert_eq!(search.path_to(&"HH".to_string()).unwrap(), ["DD", "EE", "FF", "GG", "HH"]);
     }
 
     #[test]
     fn examples_work() {
-        let input = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
-Valve BB has flow rate=13; tunnels lead to valves CC, AA
-Valve CC has flow rate=2; tunnels lead to valves DD, BB
-Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
-Valve EE has flow rate=3; tunnels lead to valves FF, DD
-Valve FF has flow rate=0; tunnels lead to valves EE, GG
-Valve GG has flow rate=0; tunnels lead to valves FF, HH
-Valve HH has flow rate=22; tunnel leads to valve GG
-Valve II has flow rate=0; tunnels lead to valves AA, JJ
-Valve JJ has flow rate=21; tunnel leads to valve II";
+        let mut volcano = Volcano::try_from(EXAMPLE).unwrap();
+        let plan = volcano.plan_alone(30);
+
+        assert_eq!(plan.pressure, 1651);
+        assert_eq!(plan.moves, [("DD", 2), ("BB", 5), ("JJ", 9), ("HH", 17), ("EE", 21), ("CC", 24)]
+            .map(|(valve, minute)| Move { valve: valve.to_string(), minute }));
 
-        let mut runner = Runner::from(input);
-        runner.run();
+        let plans = volcano.plan_with_elephant(26);
+        assert_eq!(plans[0].pressure + plans[1].pressure, 1707);
 
-        assert_eq!(runner.flown, 1651);
+        let mut opened = plans.iter().flat_map(|plan| &plan.moves).map(|m| m.valve.as_str()).collect::<Vec<_>>();
+        opened.sort();
+        assert_eq!(opened, ["BB", "CC", "DD", "EE", "HH", "JJ"]);
+    }
+
+    #[test]
+    fn too_many_valves_are_rejected() {
+        let input = (0..64)
+            .map(|i| format!("Valve V{i} has flow rate=1; tunnel leads to valve AA"))
+            .chain(["Valve AA has flow rate=0; tunnel leads to valve V0".to_string()])
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let error = Volcano::try_from(input.as_str()).err().unwrap().locate(&input);
+        assert_eq!(error.to_string(), "line 64, column 1: more than 63 valves with positive flow rate \
+            'Valve V63 has flow rate=1; tunnel leads to valve AA'");
+    }
+
+    #[test]
+    fn show_lists_the_moves() {
+        let mut out = vec![];
+        P16.show(EXAMPLE, &ShowOptions::default(), &mut out).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "you:
+  minute  2: open DD
+  minute  5: open BB
+  minute  9: open JJ
+  minute 17: open HH
+  minute 21: open EE
+  minute 24: open CC
+pressure released: 1651
+");
     }
 }
\ No newline at end of file
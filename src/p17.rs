@@ -1,20 +1,35 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::convert::identity;
 use pad::Direction::{Down, Left, Right, Up};
 use pad::{Direction, Position};
-use crate::input_reader::read_input;
+use crate::parse::ParseError;
+use crate::solver::{Answer, Solver};
 
-pub fn solve_p17() {
-    let input = read_input(17);
-    let mut runner = Runner::from(input.as_str());
-    runner.run(2022);
+pub struct P17;
 
-    println!("Solution 1: {}", runner.max_y());
+impl Solver for P17 {
+    fn day(&self) -> usize {
+        17
+    }
+
+    fn title(&self) -> &'static str {
+        "Pyroclastic Flow"
+    }
 
-    // Find patterns that repeat and than get height
-    // See https://www.reddit.com/r/adventofcode/comments/zo3vbe/2022_day_17_finding_a_pattern/
-    // let mut runner = Runner::from(input.as_str());
-    // runner.run(2022);
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        Runner::try_from(input).map(drop)
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let mut runner = Runner::try_from(input)?;
+        runner.run(2022);
+        Ok(runner.max_y().into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let runner = Runner::try_from(input)?;
+        Ok(runner.height_after(1_000_000_000_000).into())
+    }
 }
 
 struct Runner {
@@ -22,6 +37,28 @@ struct Runner {
     shape_provider: ShapeProvider,
     fields: HashSet<Position>,
     current_shape: Shape,
+    /// Highest occupied y of every column, -1 if empty.
+    column_tops: [isize; 7],
+    /// Tower height after the given amount of placed rocks.
+    heights: Vec<isize>,
+}
+
+/// A repeating part of the rock fall. After `start` rocks, every `length` rocks
+/// grow the tower by `height` units.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+    pub height: isize,
+}
+
+/// Everything that determines how the next rocks fall: the next shape, the next jet and
+/// the depth of every column below the top of the tower.
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct State {
+    shape_index: usize,
+    stream_index: usize,
+    surface: [isize; 7],
 }
 
 impl Runner {
@@ -30,9 +67,62 @@ impl Runner {
     }
 
     fn run(&mut self, bound: usize) {
-        let mut rocks_placed = 0;
+        for _ in 0..bound {
+            self.drop_rock()
+        }
+    }
+
+    /// Detect the first repetition of the state after placing a rock. The state includes
+    /// the surface of the tower, so it might be found later than the actual cycle starts.
+    fn find_cycle(&mut self) -> Cycle {
+        let mut seen = HashMap::new();
+
+        loop {
+            let rocks = self.rocks_placed();
+
+            if let Some(start) = seen.insert(self.state(), rocks) {
+                return Cycle {
+                    start,
+                    length: rocks - start,
+                    height: self.heights[rocks] - self.heights[start],
+                };
+            }
+
+            self.drop_rock()
+        }
+    }
+
+    /// The tower height after the given amount of rocks. Only simulates until a cycle is
+    /// found and extrapolates from there.
+    fn height_after(mut self, rocks: usize) -> isize {
+        let cycle = self.find_cycle();
+
+        if rocks < self.heights.len() {
+            return self.heights[rocks];
+        }
 
-        while rocks_placed < bound {
+        let cycles = (rocks - cycle.start) / cycle.length;
+        let remainder = (rocks - cycle.start) % cycle.length;
+        self.heights[cycle.start + remainder] + cycles as isize * cycle.height
+    }
+
+    fn rocks_placed(&self) -> usize {
+        self.heights.len() - 1
+    }
+
+    fn state(&self) -> State {
+        let max_y = self.max_y();
+
+        State {
+            shape_index: self.shape_provider.index,
+            stream_index: self.stream_iter.index % self.stream_iter.stream.len(),
+            surface: self.column_tops.map(|top| max_y - top),
+        }
+    }
+
+    /// Move the current shape until it comes to rest.
+    fn drop_rock(&mut self) {
+        loop {
             match self.stream_iter.next().unwrap() {
                 Push::Left => if self.can_move_in_direction(Left) {
                     self.current_shape.move_shape(Left)
@@ -46,7 +136,7 @@ impl Runner {
                 self.current_shape.move_shape(Down)
             } else {
                 self.place_shape();
-                rocks_placed += 1;
+                return;
             }
         }
     }
@@ -64,11 +154,17 @@ impl Runner {
     }
 
     fn max_y(&self) -> isize {
-        self.fields.iter().map(|pos| pos.y).max().unwrap_or(0) + 1
+        self.column_tops.into_iter().max().unwrap() + 1
     }
 
     fn place_shape(&mut self) {
-        self.current_shape.positions().into_iter().for_each(|pos| { self.fields.insert(pos); });
+        for pos in self.current_shape.positions() {
+            self.fields.insert(pos);
+            let top = &mut self.column_tops[pos.x as usize];
+            *top = (*top).max(pos.y);
+        }
+
+        self.heights.push(self.max_y());
         self.current_shape = self.next_shape();
     }
 
@@ -77,18 +173,22 @@ impl Runner {
     }
 }
 
-impl From<&str> for Runner {
-    fn from(input: &str) -> Self {
+impl TryFrom<&str> for Runner {
+    type Error = ParseError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
         let mut shape_iter = ShapeProvider::new();
         let current_shape = shape_iter.next_at_y(3);
 
-        Runner {
-            stream_iter: Stream::from(input).into_iter(),
+        Ok(Runner {
+            stream_iter: Stream::try_from(input)?.into_iter(),
             shape_provider: shape_iter,
             // Max 5 rocks, 2022 times
             fields: HashSet::with_capacity(5 * 2022),
             current_shape,
-        }
+            column_tops: [-1; 7],
+            heights: vec![0],
+        })
     }
 }
 
@@ -274,18 +374,26 @@ enum Push {
     Right,
 }
 
-impl From<&str> for Stream {
-    fn from(input: &str) -> Self {
-        Stream {
+impl TryFrom<&str> for Stream {
+    type Error = ParseError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let input = input.trim_end();
+
+        if input.is_empty() {
+            return Err(ParseError::missing(input, "at least one jet"));
+        }
+
+        Ok(Stream {
             pushes: input
-                .chars()
-                .map(|c| match c {
-                    '<' => Push::Left,
-                    '>' => Push::Right,
-                    _ => panic!("unknown")
+                .char_indices()
+                .map(|(i, c)| match c {
+                    '<' => Ok(Push::Left),
+                    '>' => Ok(Push::Right),
+                    _ => Err(ParseError::new(&input[i..i + c.len_utf8()], "unknown jet"))
                 })
-                .collect()
-        }
+                .collect::<Result<_, _>>()?
+        })
     }
 }
 
@@ -296,7 +404,7 @@ mod tests {
     #[test]
     fn stream_from_str_works() {
         let input = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
-        let stream = Stream::from(input);
+        let stream = Stream::try_from(input).unwrap();
         println!("{:?}", stream)
     }
 
@@ -316,14 +424,29 @@ mod tests {
     #[test]
     fn example_works() {
         let input = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
-        let mut runner = Runner::from(input);
+        let mut runner = Runner::try_from(input).unwrap();
         runner.run(2022);
         assert_eq!(runner.max_y(), 3068);
 
-        // let foo = 1_000_000_000_000;
-        //
-        // let mut runner = Runner::from(input);
-        // runner.run(1000000000000);
-        // assert_eq!(runner.max_y(), 1514285714288);
+        let runner = Runner::try_from(input).unwrap();
+        assert_eq!(runner.height_after(2022), 3068);
+
+        let runner = Runner::try_from(input).unwrap();
+        assert_eq!(runner.height_after(1_000_000_000_000), 1514285714288);
+    }
+
+    #[test]
+    fn find_cycle_works() {
+        let input = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+        let mut runner = Runner::try_from(input).unwrap();
+        let cycle = runner.find_cycle();
+
+        assert_eq!(cycle.length, 35);
+        assert_eq!(cycle.height, 53);
+
+        // The tower repeats after the start
+        let start_height = runner.heights[cycle.start];
+        runner.run(cycle.length);
+        assert_eq!(runner.heights[cycle.start + 2 * cycle.length] - start_height, 2 * cycle.height);
     }
 }
\ No newline at end of file
@@ -1,39 +1,64 @@
+use std::str::FromStr;
 use Hand::*;
 use Strategy::*;
-use crate::input_reader::read_input;
-
-pub fn solve_p2() {
-    let input = read_input(2);
-
-    let score_one = input.lines()
-        .map(|line| {
-            let mut split = line.split(" ");
-            (Hand::from(split.next().unwrap()), Hand::from(split.next().unwrap()))
-        })
-        .map(|(opp_hand, my_hand)| my_hand.points() + match (opp_hand, my_hand) {
-            (Rock, Paper) => 6,
-            (Rock, Scissors) => 0,
-            (Rock, Rock) => 3,
-            (Paper, Rock) => 0,
-            (Paper, Scissors) => 6,
-            (Paper, Paper) => 3,
-            (Scissors, Rock) => 6,
-            (Scissors, Paper) => 0,
-            (Scissors, Scissors) => 3,
-        })
-        .sum::<usize>();
-
-    println!("Solution 1: {score_one}");
-
-    let score_two = input.lines()
-        .map(|line| {
-            let mut split = line.split(" ");
-            (Hand::from(split.next().unwrap()), Strategy::from(split.next().unwrap()))
-        })
-        .map(|(opp_hand, strategy)| strategy.points() + strategy.answer_to_hand(opp_hand).points())
-        .sum::<usize>();
-
-    println!("Solution 2: {score_two}");
+use crate::parse::{next_part, ParseError};
+use crate::solver::{Answer, Solver};
+
+pub struct P2;
+
+impl Solver for P2 {
+    fn day(&self) -> usize {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "Rock Paper Scissors"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        input.lines().try_for_each(|line| parse_round::<Hand>(line).map(drop))
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let score_one = input.lines()
+            .map(parse_round::<Hand>)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(|(opp_hand, my_hand)| my_hand.points() + match (opp_hand, my_hand) {
+                (Rock, Paper) => 6,
+                (Rock, Scissors) => 0,
+                (Rock, Rock) => 3,
+                (Paper, Rock) => 0,
+                (Paper, Scissors) => 6,
+                (Paper, Paper) => 3,
+                (Scissors, Rock) => 6,
+                (Scissors, Paper) => 0,
+                (Scissors, Scissors) => 3,
+            })
+            .sum::<usize>();
+
+        Ok(score_one.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let score_two = input.lines()
+            .map(parse_round::<Strategy>)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(|(opp_hand, strategy)| strategy.points() + strategy.answer_to_hand(opp_hand).points())
+            .sum::<usize>();
+
+        Ok(score_two.into())
+    }
+}
+
+/// Parse a line consisting of the opponents hand and a second letter.
+fn parse_round<T: FromStr<Err=ParseError>>(line: &str) -> Result<(Hand, T), ParseError> {
+    let mut split = line.split(' ');
+    let hand = next_part(&mut split, line, "the opponents hand")?.parse()?;
+    let second = next_part(&mut split, line, "a second letter")?.parse()?;
+
+    Ok((hand, second))
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -53,13 +78,15 @@ impl Hand {
     }
 }
 
-impl From<&str> for Hand {
-    fn from(s: &str) -> Self {
+impl FromStr for Hand {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "A" | "X" => Rock,
-            "B" | "Y" => Paper,
-            "C" | "Z" => Scissors,
-            _ => panic!("unknown letter")
+            "A" | "X" => Ok(Rock),
+            "B" | "Y" => Ok(Paper),
+            "C" | "Z" => Ok(Scissors),
+            _ => Err(ParseError::new(s, "unknown hand"))
         }
     }
 }
@@ -94,13 +121,15 @@ impl Strategy {
     }
 }
 
-impl From<&str> for Strategy {
-    fn from(s: &str) -> Self {
+impl FromStr for Strategy {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "X" => Lose,
-            "Y" => Draw,
-            "Z" => Win,
-            _ => panic!("unknown letter")
+            "X" => Ok(Lose),
+            "Y" => Ok(Draw),
+            "Z" => Ok(Win),
+            _ => Err(ParseError::new(s, "unknown strategy"))
         }
     }
 }
\ No newline at end of file
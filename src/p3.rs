@@ -1,37 +1,67 @@
-use crate::input_reader::read_input;
-
-pub fn solve_p3() {
-    let input = read_input(3);
-
-    let priority_sum = input.lines()
-        .map(|line| {
-            let half_len = line.len() / 2;
-
-            line.chars()
-                .take(half_len)
-                .filter(move |char| line.chars()
-                    .skip(half_len)
-                    .any(|other_char| *char == other_char))
-                .next()
-                .unwrap()
-        })
-        .map(char_priority)
-        .sum::<usize>();
-
-    println!("Solution 1: {priority_sum}");
-
-    let priority_badge_sum = input.lines()
-        .map(String::from)
-        .batch_three()
-        .map(|batch| batch[0]
-            .chars()
-            .filter(|char| batch[1].contains(*char) && batch[2].contains(*char))
-            .next()
-            .unwrap())
-        .map(char_priority)
-        .sum::<usize>();
-
-    println!("Solution 2: {priority_badge_sum}")
+use crate::parse::ParseError;
+use crate::solver::{Answer, Solver};
+
+pub struct P3;
+
+impl Solver for P3 {
+    fn day(&self) -> usize {
+        3
+    }
+
+    fn title(&self) -> &'static str {
+        "Rucksack Reorganization"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        input.lines().try_for_each(|line| parse_rucksack(line).map(drop))
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let priority_sum = input.lines()
+            .map(|line| {
+                let line = parse_rucksack(line)?;
+                let half_len = line.len() / 2;
+
+                line.chars()
+                    .take(half_len)
+                    .find(|char| line.chars()
+                        .skip(half_len)
+                        .any(|other_char| *char == other_char))
+                    .ok_or_else(|| ParseError::new(line, "no item is in both compartments"))
+            })
+            .map(|item| item.map(char_priority))
+            .sum::<Result<usize, _>>()?;
+
+        Ok(priority_sum.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let priority_badge_sum = input.lines()
+            .map(parse_rucksack)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(String::from)
+            .batch_three()
+            .map(|batch| batch[0]
+                .chars()
+                .find(|char| batch[1].contains(*char) && batch[2].contains(*char))
+                .ok_or_else(|| ParseError::new(&batch[0], "no badge is in all three rucksacks")))
+            .map(|badge| badge.map(char_priority))
+            .sum::<Result<usize, _>>()?;
+
+        Ok(priority_badge_sum.into())
+    }
+}
+
+fn parse_rucksack(line: &str) -> Result<&str, ParseError> {
+    if let Some((i, c)) = line.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+        return Err(ParseError::new(&line[i..i + c.len_utf8()], "unknown item"));
+    }
+
+    match line.len() % 2 {
+        0 => Ok(line),
+        _ => Err(ParseError::new(line, "rucksack has an odd number of items"))
+    }
 }
 
 fn char_priority(char: char) -> usize {
@@ -1,21 +1,43 @@
-use crate::input_reader::read_input;
+use std::str::FromStr;
+use crate::parse::{next_part, parse_number, ParseError};
+use crate::solver::{Answer, Solver};
 
-pub fn solve_p4() {
-    let input = read_input(4);
+pub struct P4;
 
-    let num_full_contains = input.lines()
-        .map(RangePair::from)
-        .filter(RangePair::one_contains_the_other)
-        .count();
+impl Solver for P4 {
+    fn day(&self) -> usize {
+        4
+    }
+
+    fn title(&self) -> &'static str {
+        "Camp Cleanup"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_range_pairs(input).map(drop)
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let num_full_contains = parse_range_pairs(input)?
+            .iter()
+            .filter(|pair| pair.one_contains_the_other())
+            .count();
+
+        Ok(num_full_contains.into())
+    }
 
-    println!("Solution 1: {num_full_contains}");
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let num_overlaps = parse_range_pairs(input)?
+            .iter()
+            .filter(|pair| pair.one_overlaps_with_the_other())
+            .count();
 
-    let num_overlaps = input.lines()
-        .map(RangePair::from)
-        .filter(RangePair::one_overlaps_with_the_other)
-        .count();
+        Ok(num_overlaps.into())
+    }
+}
 
-    println!("Solution 2: {num_overlaps}")
+fn parse_range_pairs(input: &str) -> Result<Vec<RangePair>, ParseError> {
+    input.lines().map(RangePair::from_str).collect()
 }
 
 struct RangePair {
@@ -49,36 +71,40 @@ impl Range {
     }
 }
 
-impl From<&str> for RangePair {
-    fn from(s: &str) -> Self {
-        let mut ranges = s.split(",").map(Range::from);
+impl FromStr for RangePair {
+    type Err = ParseError;
 
-        RangePair {
-            first: ranges.next().unwrap(),
-            second: ranges.next().unwrap(),
-        }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ranges = s.split(',');
+
+        Ok(RangePair {
+            first: next_part(&mut ranges, s, "a range")?.parse()?,
+            second: next_part(&mut ranges, s, "a second range")?.parse()?,
+        })
     }
 }
 
-impl From<&str> for Range {
-    fn from(s: &str) -> Self {
-        let mut start_end = s.split("-").map(|part| part.parse::<usize>().unwrap());
+impl FromStr for Range {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut start_end = s.split('-');
 
-        Range {
-            start: start_end.next().unwrap(),
-            end: start_end.next().unwrap(),
-        }
+        Ok(Range {
+            start: parse_number(next_part(&mut start_end, s, "a start")?)?,
+            end: parse_number(next_part(&mut start_end, s, "an end")?)?,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::p4::{Range, RangePair};
+    use crate::p4::{parse_range_pairs, Range, RangePair};
 
     #[test]
     fn range_from_str_works() {
         let s = "42-69";
-        let range = Range::from(s);
+        let range = s.parse::<Range>().unwrap();
 
         assert_eq!(42, range.start);
         assert_eq!(69, range.end);
@@ -87,7 +113,7 @@ mod tests {
     #[test]
     fn range_pair_from_str_works() {
         let s = "42-69,420-1337";
-        let range_pair = RangePair::from(s);
+        let range_pair = s.parse::<RangePair>().unwrap();
 
         assert_eq!(42, range_pair.first.start);
         assert_eq!(69, range_pair.first.end);
@@ -95,6 +121,14 @@ mod tests {
         assert_eq!(1337, range_pair.second.end);
     }
 
+    #[test]
+    fn range_pair_from_invalid_str_fails() {
+        let input = "1-2,3-4\n42-69,420";
+        let err = parse_range_pairs(input).err().unwrap().locate(input);
+
+        assert_eq!(err.to_string(), "line 2, column 10: expected an end");
+    }
+
     #[test]
     fn fully_contains_works() {
         let first = Range {
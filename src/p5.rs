@@ -1,34 +1,83 @@
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::str::FromStr;
+use crate::input_reader::blocks;
+use crate::parse::{next_part, parse_number, ParseError};
+use crate::solver::{Answer, Solver};
 
-use crate::input_reader::read_input;
+pub struct P5;
 
-pub fn solve_p5() {
-    let input = read_input(5);
-    let mut split = input.split("\r\n\r\n");
+impl Solver for P5 {
+    fn day(&self) -> usize {
+        5
+    }
 
-    let stacks_input = split.next().unwrap();
-    let steps_input = split.next().unwrap();
+    fn title(&self) -> &'static str {
+        "Supply Stacks"
+    }
 
-    let mut stacks = Stacks::from(stacks_input);
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(drop)
+    }
 
-    steps_input.lines()
-        .map(Step::from)
-        .for_each(|step| stacks.execute_step_9000(step));
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let (mut stacks, steps) = parse_input(input)?;
 
-    let top = stacks.top();
+        steps
+            .into_iter()
+            .for_each(|step| stacks.execute_step_9000(step));
 
-    println!("Solution 1: {top}");
+        let top = stacks.top();
 
-    let mut stacks = Stacks::from(stacks_input);
+        Ok(top.into())
+    }
 
-    steps_input.lines()
-        .map(Step::from)
-        .for_each(|step| stacks.execute_step_9001(step));
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let (mut stacks, steps) = parse_input(input)?;
 
-    let top = stacks.top();
+        steps
+            .into_iter()
+            .for_each(|step| stacks.execute_step_9001(step));
+
+        let top = stacks.top();
+
+        Ok(top.into())
+    }
+}
 
-    println!("Solution 2: {top}");
+/// Parse the stacks and the steps, which must only move existing crates between existing stacks.
+fn parse_input(input: &str) -> Result<(Stacks, Vec<Step>), ParseError> {
+    let mut split = blocks(input);
+    let stacks = Stacks::from(next_part(&mut split, input, "the stacks")?);
+    let mut heights = stacks.stacks
+        .iter()
+        .map(|(index, stack)| (*index, stack.len()))
+        .collect::<HashMap<_, _>>();
+
+    let steps = next_part(&mut split, input, "the steps after a blank line")?
+        .lines()
+        .map(|line| {
+            let step = Step::from_str(line)?;
+
+            for stack in [step.start, step.target] {
+                if !heights.contains_key(&stack) {
+                    return Err(ParseError::new(line, format!("there is no stack {stack}")));
+                }
+            }
+
+            let start_height = heights[&step.start];
+
+            if start_height < step.amount {
+                return Err(ParseError::new(line, format!("stack {} only holds {start_height} crates", step.start)));
+            }
+
+            heights.insert(step.start, start_height - step.amount);
+            *heights.get_mut(&step.target).expect("should be set") += step.amount;
+            Ok(step)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((stacks, steps))
 }
 
 #[derive(Debug)]
@@ -72,10 +121,11 @@ impl Stacks {
         values.into_iter().for_each(|c| self.stacks.get_mut(&target).expect("should be set").push(c))
     }
 
+    /// The crates on top of the stacks, skipping empty stacks.
     fn top(&self) -> String {
         (1..=self.stacks.len())
             .map(|i| self.stacks.get(&i).expect("should be set"))
-            .map(|stack| stack.last().expect("should be set"))
+            .filter_map(|stack| stack.last())
             .collect()
     }
 }
@@ -84,6 +134,12 @@ impl From<&str> for Stacks {
     fn from(input: &str) -> Self {
         let mut stacks = Stacks::new();
 
+        // The last line numbers all stacks, including those without crates
+        let num_stacks = input.lines().last().map_or(0, |labels| labels.split_whitespace().count());
+        (1..=num_stacks).for_each(|i| {
+            stacks.stacks.entry(i).or_default();
+        });
+
         input.lines()
             .rev()
             .skip(1)
@@ -110,37 +166,53 @@ struct Step {
     amount: usize,
 }
 
-impl From<&str> for Step {
-    fn from(input: &str) -> Self {
-        let mut values = input
-            .split(" ")
-            .filter_map(|part| match part.parse::<usize>() {
-                Ok(val) => Some(val),
-                _ => None
-            });
-
-        Step {
-            amount: values.next().unwrap(),
-            start: values.next().unwrap(),
-            target: values.next().unwrap(),
-        }
+impl FromStr for Step {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parts = input.split(' ');
+
+        let mut value_after = |keyword: &str| {
+            let part = next_part(&mut parts, input, &format!("'{keyword}'"))?;
+
+            if part != keyword {
+                return Err(ParseError::new(part, format!("expected '{keyword}'")));
+            }
+
+            parse_number::<usize>(next_part(&mut parts, input, &format!("a number after '{keyword}'"))?)
+        };
+
+        Ok(Step {
+            amount: value_after("move")?,
+            start: value_after("from")?,
+            target: value_after("to")?,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::p5::{Stacks, Step};
+    use crate::p5::{parse_input, Stacks, Step};
 
     #[test]
     fn step_from_str_works() {
         let string = "move 4 from 2 to 1";
-        let step = Step::from(string);
+        let step = string.parse::<Step>().unwrap();
 
         assert_eq!(2, step.start);
         assert_eq!(1, step.target);
         assert_eq!(4, step.amount);
     }
 
+    #[test]
+    fn step_from_invalid_str_fails() {
+        let err = "move 4 form 2 to 1".parse::<Step>().err().unwrap();
+        assert_eq!(err.to_string(), "expected 'from' 'form'");
+
+        let err = "move 4 from x to 1".parse::<Step>().err().unwrap();
+        assert_eq!(err.to_string(), "expected a number 'x'");
+    }
+
     #[test]
     fn stacks_from_str_works() {
         let string = "\
@@ -192,4 +264,21 @@ mod tests {
         let stacks = Stacks::from(string);
         println!("{}", stacks.top())
     }
+
+    #[test]
+    fn invalid_steps_are_rejected() {
+        let stacks = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 ";
+
+        let input = format!("{stacks}\n\nmove 1 from 2 to 3\nmove 1 from 3 to 3");
+        assert!(parse_input(&input).is_ok());
+
+        let input = format!("{stacks}\n\nmove 1 from 2 to 1\nmove 1 from 4 to 1");
+        assert_eq!(parse_input(&input).err().unwrap().locate(&input).to_string(), "line 7, column 1: there is no stack 4 'move 1 from 4 to 1'");
+
+        let input = format!("{stacks}\n\nmove 2 from 1 to 3\nmove 2 from 3 to 2\nmove 2 from 1 to 2");
+        assert_eq!(parse_input(&input).err().unwrap().locate(&input).to_string(), "line 8, column 1: stack 1 only holds 0 crates 'move 2 from 1 to 2'");
+    }
 }
\ No newline at end of file
@@ -1,32 +1,51 @@
 use std::collections::HashSet;
-use crate::input_reader::read_input;
+use crate::parse::ParseError;
+use crate::solver::{Answer, Solver};
 
-pub fn solve_p6() {
-    let input = read_input(6);
-    let index = get_index_after_start_marker(&input);
-    println!("Solution 1: {index}");
+pub struct P6;
 
-    let index = get_index_after_message_marker(&input);
-    println!("Solution 2: {index}");
+impl Solver for P6 {
+    fn day(&self) -> usize {
+        6
+    }
+
+    fn title(&self) -> &'static str {
+        "Tuning Trouble"
+    }
+
+    fn parse(&self, _input: &str) -> Result<(), ParseError> {
+        // The datastream is searched as it is
+        Ok(())
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let index = get_index_after_start_marker(input)?;
+        Ok(index.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let index = get_index_after_message_marker(input)?;
+        Ok(index.into())
+    }
 }
 
-fn get_index_after_start_marker(input: &str) -> usize {
+fn get_index_after_start_marker(input: &str) -> Result<usize, ParseError> {
     get_index_after_n_distinct::<4>(input)
 }
 
-fn get_index_after_message_marker(input: &str) -> usize {
+fn get_index_after_message_marker(input: &str) -> Result<usize, ParseError> {
     get_index_after_n_distinct::<14>(input)
 }
 
-fn get_index_after_n_distinct<const N: usize>(input: &str) -> usize {
+fn get_index_after_n_distinct<const N: usize>(input: &str) -> Result<usize, ParseError> {
     input.chars()
         .collect::<Vec<_>>()
         .windows(N)
         .enumerate()
-        .filter(|(_, w)| HashSet::<&char>::from_iter(w.into_iter()).len() == N)
+        .filter(|(_, w)| HashSet::<&char>::from_iter(w.iter()).len() == N)
         .map(|(i, _)| i + N)
         .next()
-        .expect("no marker")
+        .ok_or_else(|| ParseError::missing(input, &format!("a marker of {N} distinct characters")))
 }
 
 #[cfg(test)]
@@ -43,7 +62,7 @@ mod tests {
         ];
 
         for (input, expected) in inputs_expected.into_iter() {
-            assert_eq!(get_index_after_start_marker(input), expected);
+            assert_eq!(get_index_after_start_marker(input).unwrap(), expected);
         }
     }
 
@@ -57,7 +76,7 @@ mod tests {
         ];
 
         for (input, expected) in inputs_expected.into_iter() {
-            assert_eq!(get_index_after_message_marker(input), expected);
+            assert_eq!(get_index_after_message_marker(input).unwrap(), expected);
         }
     }
 }
\ No newline at end of file
@@ -1,20 +1,43 @@
 use std::collections::{HashMap, HashSet};
 use std::collections::hash_map::Entry;
-use crate::input_reader::read_input;
+use std::str::FromStr;
 use crate::p7::Output::{ChangeTo, ChangeUp, Directory, File, List};
+use crate::parse::{next_part, parse_number, ParseError};
+use crate::solver::{Answer, Solver};
 
-pub fn solve_p7() {
-    let input = read_input(7);
+pub struct P7;
 
-    let mut state_machine = StateMachine::new();
-    state_machine.process_outputs(input.lines().flat_map(Output::from_str));
-    let sum_dirs = state_machine.sum_of_dirs_with_at_most_100k();
+impl Solver for P7 {
+    fn day(&self) -> usize {
+        7
+    }
 
-    println!("Solution 1: {sum_dirs}");
+    fn title(&self) -> &'static str {
+        "No Space Left On Device"
+    }
 
-    let size_deletable = state_machine.size_of_deletable_directory();
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        create_state_machine(input).map(drop)
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let sum_dirs = create_state_machine(input)?.sum_of_dirs_with_at_most_100k();
+
+        Ok(sum_dirs.into())
+    }
 
-    println!("Solution 2: {size_deletable}");
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let size_deletable = create_state_machine(input)?.size_of_deletable_directory();
+
+        Ok(size_deletable.into())
+    }
+}
+
+fn create_state_machine(input: &str) -> Result<StateMachine, ParseError> {
+    let outputs = input.lines().map(Output::from_str).collect::<Result<Vec<_>, _>>()?;
+    let mut state_machine = StateMachine::new();
+    state_machine.process_outputs(outputs);
+    Ok(state_machine)
 }
 
 struct StateMachine {
@@ -129,28 +152,30 @@ enum Output {
     File(usize),
 }
 
-impl Output {
-    fn from_str(s: &str) -> Option<Output> {
-        let parts = s.split(" ").collect::<Vec<_>>();
+impl FromStr for Output {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(' ');
 
-        match parts[0] {
-            "$" => match parts[1] {
-                "cd" => match parts[2] {
-                    ".." => Some(ChangeUp),
-                    s => Some(ChangeTo(s.to_string()))
+        match next_part(&mut parts, s, "a command or directory entry")? {
+            "$" => match next_part(&mut parts, s, "a command")? {
+                "cd" => match next_part(&mut parts, s, "a directory")? {
+                    ".." => Ok(ChangeUp),
+                    dir => Ok(ChangeTo(dir.to_string()))
                 },
-                "ls" => Some(List),
-                _ => None
+                "ls" => Ok(List),
+                command => Err(ParseError::new(command, "unknown command"))
             },
-            "dir" => Some(Directory(parts[1].to_string())),
-            num if num.parse::<usize>().is_ok() => Some(File(num.parse::<usize>().unwrap())),
-            _ => None
+            "dir" => Ok(Directory(next_part(&mut parts, s, "a directory")?.to_string())),
+            size => Ok(File(parse_number(size)?))
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
     use crate::p7::{Output, StateMachine};
     use crate::p7::Output::*;
 
@@ -159,13 +184,15 @@ mod tests {
         let input_expected = [
             ("$ cd jmdf", Some(ChangeTo("jmdf".to_string()))),
             ("$ cd ..", Some(ChangeUp)),
-            ("$ ls", None),
+            ("$ ls", Some(List)),
             ("dir jmdf", Some(Directory("jmdf".to_string()))),
-            ("177917 pvlvsfjw.qvw", Some(File(177917)))
+            ("177917 pvlvsfjw.qvw", Some(File(177917))),
+            ("$ rm jmdf", None),
+            ("pvlvsfjw.qvw", None)
         ];
 
         for (input, expected) in input_expected {
-            assert_eq!(Output::from_str(input), expected)
+            assert_eq!(Output::from_str(input).ok(), expected)
         }
     }
 
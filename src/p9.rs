@@ -1,20 +1,42 @@
 use std::cmp::Ordering;
 use std::collections::HashSet;
-use crate::input_reader::read_input;
+use std::str::FromStr;
 use Direction::*;
+use crate::parse::{next_part, parse_number, ParseError};
+use crate::solver::{Answer, Solver};
 
-pub fn solve_p9() {
-    let input = read_input(9);
+pub struct P9;
 
-    let mut board = Board::<2>::new();
-    board.process_all_steps(input.lines().map(Steps::from));
-    let num_visited_tail_positions = board.num_tail_visited_positions();
-    println!("Solution 1: {num_visited_tail_positions}");
+impl Solver for P9 {
+    fn day(&self) -> usize {
+        9
+    }
+
+    fn title(&self) -> &'static str {
+        "Rope Bridge"
+    }
+
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
+        parse_steps(input).map(drop)
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let mut board = Board::<2>::new();
+        board.process_all_steps(parse_steps(input)?);
+        let num_visited_tail_positions = board.num_tail_visited_positions();
+        Ok(num_visited_tail_positions.into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        let mut board = Board::<10>::new();
+        board.process_all_steps(parse_steps(input)?);
+        let num_visited_tail_positions = board.num_tail_visited_positions();
+        Ok(num_visited_tail_positions.into())
+    }
+}
 
-    let mut board = Board::<10>::new();
-    board.process_all_steps(input.lines().map(Steps::from));
-    let num_visited_tail_positions = board.num_tail_visited_positions();
-    println!("Solution 2: {num_visited_tail_positions}");
+fn parse_steps(input: &str) -> Result<Vec<Steps>, ParseError> {
+    input.lines().map(Steps::from_str).collect()
 }
 
 struct Board<const C: usize> {
@@ -115,14 +137,16 @@ struct Steps {
     amount: usize,
 }
 
-impl From<&str> for Steps {
-    fn from(s: &str) -> Self {
-        let split = s.split(" ").collect::<Vec<_>>();
+impl FromStr for Steps {
+    type Err = ParseError;
 
-        Steps {
-            direction: Direction::from(split[0]),
-            amount: split[1].parse::<usize>().expect("should be usize"),
-        }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut split = s.split(' ');
+
+        Ok(Steps {
+            direction: next_part(&mut split, s, "a direction")?.parse()?,
+            amount: parse_number(next_part(&mut split, s, "an amount")?)?,
+        })
     }
 }
 
@@ -134,21 +158,23 @@ enum Direction {
     Down,
 }
 
-impl From<&str> for Direction {
-    fn from(s: &str) -> Self {
+impl FromStr for Direction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "D" => Down,
-            "U" => Up,
-            "R" => Right,
-            "L" => Left,
-            _ => panic!("unknown direction")
+            "D" => Ok(Down),
+            "U" => Ok(Up),
+            "R" => Ok(Right),
+            "L" => Ok(Left),
+            _ => Err(ParseError::new(s, "unknown direction"))
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::p9::{Board, Steps};
+    use crate::p9::{Board, parse_steps};
 
     #[test]
     fn examples_work() {
@@ -162,7 +188,7 @@ L 5
 R 2";
 
         let mut board = Board::<2>::new();
-        board.process_all_steps(input_one.lines().map(Steps::from));
+        board.process_all_steps(parse_steps(input_one).unwrap());
         assert_eq!(board.num_tail_visited_positions(), 13);
 
         let input_two = "R 5
@@ -175,7 +201,14 @@ L 25
 U 20";
 
         let mut board = Board::<10>::new();
-        board.process_all_steps(input_two.lines().map(Steps::from));
+        board.process_all_steps(parse_steps(input_two).unwrap());
         assert_eq!(board.num_tail_visited_positions(), 36);
     }
+
+    #[test]
+    fn invalid_steps_fail() {
+        let input = "R 4\nX 4";
+        let err = parse_steps(input).err().unwrap().locate(input);
+        assert_eq!(err.to_string(), "line 2, column 1: unknown direction 'X'");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,123 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Error for malformed puzzle input.
+///
+/// Parsers create it from the offending slice of the input. The caller that owns the whole
+/// input later resolves line and column with [ParseError::locate] and sets the day.
+#[derive(Clone, Debug)]
+pub struct ParseError {
+    day: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    text: String,
+    reason: String,
+    /// Address of the offending slice, used to find it in the input.
+    address: usize,
+}
+
+impl ParseError {
+    pub fn new(text: &str, reason: impl Into<String>) -> Self {
+        ParseError {
+            day: None,
+            line: None,
+            column: None,
+            text: text.to_string(),
+            reason: reason.into(),
+            address: text.as_ptr() as usize,
+        }
+    }
+
+    /// Error for something missing at the end of the given text.
+    pub fn missing(text: &str, what: &str) -> Self {
+        ParseError::new(&text[text.len()..], format!("expected {what}"))
+    }
+
+    /// Determine line and column (both starting at 1) of the offending text in the given input.
+    /// If the text is not a slice of the input, its first occurrence is used.
+    pub fn locate(mut self, input: &str) -> Self {
+        let start = input.as_ptr() as usize;
+
+        let offset = match (start..=start + input.len()).contains(&self.address) {
+            true => Some(self.address - start),
+            false => input.find(&self.text)
+        };
+
+        if let Some(offset) = offset {
+            let before = &input[..offset];
+            let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+            self.line = Some(before.matches('\n').count() + 1);
+            self.column = Some(before[line_start..].chars().count() + 1);
+        }
+
+        self
+    }
+
+    pub fn in_day(mut self, day: usize) -> Self {
+        self.day = Some(day);
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let location = [("day", self.day), ("line", self.line), ("column", self.column)]
+            .into_iter()
+            .filter_map(|(name, val)| val.map(|val| format!("{name} {val}")))
+            .collect::<Vec<_>>();
+
+        if !location.is_empty() {
+            write!(f, "{}: ", location.join(", "))?;
+        }
+
+        match self.text.is_empty() {
+            true => write!(f, "{}", self.reason),
+            false => write!(f, "{} '{}'", self.reason, self.text)
+        }
+    }
+}
+
+/// Parse a number, failing with a [ParseError] pointing at the text.
+pub fn parse_number<T: FromStr>(text: &str) -> Result<T, ParseError> {
+    text.parse::<T>().map_err(|_| ParseError::new(text, "expected a number"))
+}
+
+/// Take the next item from the given iterator over parts of `text`, failing if it is exhausted.
+pub fn next_part<'a>(parts: &mut impl Iterator<Item=&'a str>, text: &'a str, what: &str) -> Result<&'a str, ParseError> {
+    parts.next().ok_or_else(|| ParseError::missing(text, what))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::{next_part, parse_number, ParseError};
+
+    #[test]
+    fn locate_works() {
+        let input = "1 2\n3 x\n5 6";
+        let line = input.lines().nth(1).unwrap();
+        let err = parse_number::<usize>(&line[2..]).unwrap_err().locate(input).in_day(4);
+
+        assert_eq!(err.day, Some(4));
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.column, Some(3));
+        assert_eq!(err.text, "x");
+        assert_eq!(err.to_string(), "day 4, line 2, column 3: expected a number 'x'");
+    }
+
+    #[test]
+    fn locate_copied_text_works() {
+        // Owned text, which is not a slice of the input
+        let text = String::from("y");
+        let err = ParseError::new(&text, "unknown letter").locate("x\nxy");
+        assert_eq!((err.line, err.column), (Some(2), Some(2)));
+    }
+
+    #[test]
+    fn missing_part_points_to_end() {
+        let input = "A";
+        let err = next_part(&mut input.split(' ').skip(1), input, "a second letter").unwrap_err().locate(input);
+
+        assert_eq!((err.line, err.column), (Some(1), Some(2)));
+        assert_eq!(err.to_string(), "line 1, column 2: expected a second letter");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,237 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// The outcome of a search from one or more start nodes: the distance to every reached node
+/// and the predecessor on a shortest path, used to reconstruct paths.
+#[derive(Debug)]
+pub struct Search<N> {
+    distances: HashMap<N, usize>,
+    came_from: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn new() -> Self {
+        Search {
+            distances: HashMap::new(),
+            came_from: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<usize> {
+        self.distances.get(node).copied()
+    }
+
+    /// A shortest path from one of the starts to the node, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.distances.get(node)?;
+
+        let mut path = vec![node.clone()];
+
+        while let Some(previous) = self.came_from.get(path.last().unwrap()) {
+            path.push(previous.clone())
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth first search where every step costs 1.
+pub fn bfs<N, I>(starts: impl IntoIterator<Item=N>, mut neighbours: impl FnMut(&N) -> I) -> Search<N>
+    where N: Clone + Eq + Hash,
+          I: IntoIterator<Item=N> {
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if search.distances.insert(start.clone(), 0).is_none() {
+            queue.push_back(start)
+        }
+    }
+
+    while let Some(current) = queue.pop_front() {
+        let distance = search.distances[&current] + 1;
+
+        for next in neighbours(&current) {
+            if !search.distances.contains_key(&next) {
+                search.distances.insert(next.clone(), distance);
+                search.came_from.insert(next.clone(), current.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    search
+}
+
+/// Dijkstra's algorithm for non-negative step costs, visiting every reachable node.
+pub fn dijkstra<N, I>(starts: impl IntoIterator<Item=N>, neighbours: impl FnMut(&N) -> I) -> Search<N>
+    where N: Clone + Eq + Hash,
+          I: IntoIterator<Item=(N, usize)> {
+    best_first(starts, |_| false, neighbours, |_| 0).0
+}
+
+/// A* search from the starts to the first node matching the goal. The heuristic must never
+/// overestimate the remaining cost. Returns the cost and the path (starts and goal included).
+#[allow(dead_code)]
+pub fn a_star<N, I>(
+    starts: impl IntoIterator<Item=N>,
+    goal: impl Fn(&N) -> bool,
+    neighbours: impl FnMut(&N) -> I,
+    heuristic: impl Fn(&N) -> usize,
+) -> Option<(usize, Vec<N>)>
+    where N: Clone + Eq + Hash,
+          I: IntoIterator<Item=(N, usize)> {
+    let (search, reached) = best_first(starts, goal, neighbours, heuristic);
+    let reached = reached?;
+    Some((search.distance(&reached)?, search.path_to(&reached)?))
+}
+
+/// The distances between all given nodes, searching from each of them.
+pub fn all_pairs<N, I>(nodes: &[N], mut neighbours: impl FnMut(&N) -> I) -> HashMap<N, HashMap<N, usize>>
+    where N: Clone + Eq + Hash,
+          I: IntoIterator<Item=(N, usize)> {
+    nodes
+        .iter()
+        .map(|from| {
+            let search = dijkstra([from.clone()], &mut neighbours);
+            let distances = nodes
+                .iter()
+                .filter_map(|to| search.distance(to).map(|distance| (to.clone(), distance)))
+                .collect();
+            (from.clone(), distances)
+        })
+        .collect()
+}
+
+/// Node in the priority queue, ordered by its estimated total cost (lowest first).
+struct Queued<N> {
+    estimate: usize,
+    cost: usize,
+    node: N,
+}
+
+impl<N> PartialEq for Queued<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl<N> Eq for Queued<N> {}
+
+impl<N> PartialOrd for Queued<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Queued<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.cmp(&self.estimate)
+    }
+}
+
+/// Shared implementation of Dijkstra and A*. Stops at the first node matching the goal.
+fn best_first<N, I>(
+    starts: impl IntoIterator<Item=N>,
+    goal: impl Fn(&N) -> bool,
+    mut neighbours: impl FnMut(&N) -> I,
+    heuristic: impl Fn(&N) -> usize,
+) -> (Search<N>, Option<N>)
+    where N: Clone + Eq + Hash,
+          I: IntoIterator<Item=(N, usize)> {
+    let mut search = Search::new();
+    let mut queue = BinaryHeap::new();
+
+    for start in starts {
+        search.distances.insert(start.clone(), 0);
+        queue.push(Queued { estimate: heuristic(&start), cost: 0, node: start });
+    }
+
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        // Outdated entry, the node was reached cheaper in the meantime
+        if cost > search.distances[&node] {
+            continue;
+        }
+
+        if goal(&node) {
+            return (search, Some(node));
+        }
+
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+
+            if search.distances.get(&next).is_none_or(|known| next_cost < *known) {
+                search.distances.insert(next.clone(), next_cost);
+                search.came_from.insert(next.clone(), node.clone());
+                queue.push(Queued { estimate: next_cost + heuristic(&next), cost: next_cost, node: next });
+            }
+        }
+    }
+
+    (search, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use crate::search::{a_star, all_pairs, bfs, dijkstra};
+
+    /// a - b - c - d in a line, with a shortcut a - d of cost 5 and a costly step c - d of 10.
+    fn weighted(node: &char) -> Vec<(char, usize)> {
+        match node {
+            'a' => vec![('b', 1), ('d', 5)],
+            'b' => vec![('a', 1), ('c', 1)],
+            'c' => vec![('b', 1), ('d', 10)],
+            'd' => vec![('a', 5), ('c', 10)],
+            _ => vec![]
+        }
+    }
+
+    fn unweighted(node: &char) -> Vec<char> {
+        weighted(node).into_iter().map(|(next, _)| next).collect()
+    }
+
+    #[test]
+    fn bfs_works() {
+        let search = bfs(['c'], unweighted);
+
+        assert_eq!(search.distance(&'a'), Some(2));
+        assert_eq!(search.distance(&'d'), Some(1));
+        assert_eq!(search.distance(&'x'), None);
+        assert_eq!(search.path_to(&'a'), Some(vec!['c', 'b', 'a']));
+    }
+
+    #[test]
+    fn multi_source_bfs_works() {
+        let search = bfs(['a', 'd'], unweighted);
+
+        assert_eq!(['a', 'b', 'c', 'd'].map(|node| search.distance(&node)), [Some(0), Some(1), Some(1), Some(0)]);
+        assert_eq!(search.path_to(&'c'), Some(vec!['d', 'c']));
+    }
+
+    #[test]
+    fn dijkstra_works() {
+        let search = dijkstra(['c'], weighted);
+
+        assert_eq!(search.distance(&'d'), Some(7));
+        assert_eq!(search.path_to(&'d'), Some(vec!['c', 'b', 'a', 'd']));
+    }
+
+    #[test]
+    fn a_star_works() {
+        let heuristic = |node: &char| ('d' as usize - *node as usize).min(1);
+
+        assert_eq!(a_star(['c'], |node| *node == 'd', weighted, heuristic), Some((7, vec!['c', 'b', 'a', 'd'])));
+        assert_eq!(a_star(['c'], |node| *node == 'x', weighted, heuristic), None);
+    }
+
+    #[test]
+    fn all_pairs_works() {
+        let distances = all_pairs(&['a', 'c', 'd'], weighted);
+
+        assert_eq!(distances[&'a'], HashMap::from([('a', 0), ('c', 2), ('d', 5)]));
+        assert_eq!(distances[&'d'][&'c'], 7);
+    }
+}
\ No newline at end of file
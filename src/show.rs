@@ -0,0 +1,108 @@
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use crate::parse::ParseError;
+
+/// Options for visualizing a day, given as `key=value` arguments on the command line.
+/// Every day picks the keys it knows and rejects the others.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ShowOptions {
+    options: Vec<(String, String)>,
+}
+
+impl ShowOptions {
+    pub fn new(options: Vec<(String, String)>) -> Self {
+        ShowOptions { options }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Parse the value of the key, or return the default if it is not set.
+    pub fn get_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, ShowError> {
+        match self.get(key) {
+            Some(value) => value.parse().map_err(|_| ShowError::Option(format!("invalid value '{value}' for {key}"))),
+            None => Ok(default)
+        }
+    }
+
+    /// Parse the value of the key as `MIN..=MAX`, or return the default if it is not set.
+    pub fn get_range_or(&self, key: &str, default: RangeInclusive<isize>) -> Result<RangeInclusive<isize>, ShowError> {
+        let Some(value) = self.get(key) else {
+            return Ok(default);
+        };
+
+        value
+            .split_once("..=")
+            .and_then(|(min, max)| Some(min.parse().ok()?..=max.parse().ok()?))
+            .ok_or_else(|| ShowError::Option(format!("invalid range '{value}' for {key}, expected MIN..=MAX")))
+    }
+
+    /// Fail if an option is not one of the known keys.
+    pub fn check_known(&self, known: &[&str]) -> Result<(), ShowError> {
+        match self.options.iter().find(|(key, _)| !known.contains(&key.as_str())) {
+            Some((key, _)) => Err(ShowError::Option(format!("unknown option '{key}', expected one of {}", known.join(", ")))),
+            None => Ok(())
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ShowError {
+    Unsupported,
+    Option(String),
+    Parse(ParseError),
+    Io(std::io::Error),
+}
+
+impl Display for ShowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShowError::Unsupported => write!(f, "this day has no visualization"),
+            ShowError::Option(reason) => write!(f, "{reason}"),
+            ShowError::Parse(e) => write!(f, "{e}"),
+            ShowError::Io(e) => write!(f, "failed to write: {e}")
+        }
+    }
+}
+
+impl From<ParseError> for ShowError {
+    fn from(e: ParseError) -> Self {
+        ShowError::Parse(e)
+    }
+}
+
+impl From<std::io::Error> for ShowError {
+    fn from(e: std::io::Error) -> Self {
+        ShowError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::show::ShowOptions;
+
+    #[test]
+    fn options_work() {
+        let options = ShowOptions::new(vec![
+            ("scale".to_string(), "2".to_string()),
+            ("part".to_string(), "x".to_string()),
+            ("scale".to_string(), "3".to_string()),
+            ("x".to_string(), "-2..=5".to_string()),
+        ]);
+
+        assert_eq!(options.get_or("scale", 1).unwrap(), 3);
+        assert_eq!(options.get_or("speed", 5).unwrap(), 5);
+        assert_eq!(options.get_or::<usize>("part", 1).unwrap_err().to_string(), "invalid value 'x' for part");
+        assert_eq!(options.get_range_or("x", 0..=1).unwrap(), -2..=5);
+        assert_eq!(options.get_range_or("y", 0..=1).unwrap(), 0..=1);
+        assert_eq!(options.get_range_or("part", 0..=1).unwrap_err().to_string(), "invalid range 'x' for part, expected MIN..=MAX");
+        assert!(options.check_known(&["scale", "part", "x"]).is_ok());
+        assert_eq!(options.check_known(&["scale", "x"]).unwrap_err().to_string(), "unknown option 'part', expected one of scale, x");
+    }
+}
\ No newline at end of file
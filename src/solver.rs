@@ -0,0 +1,186 @@
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use crate::parse::ParseError;
+use crate::show::{ShowError, ShowOptions};
+use crate::{p1, p2, p3, p4, p5, p6, p7, p8, p9, p10, p11, p12, p13, p14, p15, p16, p17};
+
+/// The answer of a single puzzle part.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+    /// Rows of pixels, where true means lit.
+    Bitmap(Vec<Vec<bool>>),
+    /// Rows of pixels showing letters that couldn't be read, with the reason why. The caller
+    /// decides whether to report the reason.
+    Unreadable { bitmap: Vec<Vec<bool>>, reason: String },
+}
+
+impl Answer {
+    pub fn to_json(&self) -> String {
+        match self {
+            Answer::Int(val) => val.to_string(),
+            Answer::Text(text) => json_string(text),
+            Answer::Bitmap(_) | Answer::Unreadable { .. } => {
+                let rows = self.to_string()
+                    .lines()
+                    .map(json_string)
+                    .collect::<Vec<_>>();
+                format!("[{}]", rows.join(","))
+            }
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let escaped = s.chars()
+        .map(|c| match c {
+            '"' => "\\\"".to_string(),
+            '\\' => "\\\\".to_string(),
+            '\n' => "\\n".to_string(),
+            c if c.is_control() => format!("\\u{:04x}", c as u32),
+            c => c.to_string()
+        })
+        .collect::<String>();
+
+    format!("\"{escaped}\"")
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(val) => write!(f, "{val}"),
+            Answer::Text(text) => write!(f, "{text}"),
+            Answer::Bitmap(rows) | Answer::Unreadable { bitmap: rows, .. } => {
+                let rows = rows
+                    .iter()
+                    .map(|row| row.iter().map(|lit| if *lit { '#' } else { '.' }).collect::<String>())
+                    .collect::<Vec<_>>();
+                write!(f, "{}", rows.join("\n"))
+            }
+        }
+    }
+}
+
+macro_rules! impl_answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(val: $t) -> Self {
+                    Answer::Int(i128::try_from(val).expect("answer should fit into i128"))
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from_int!(usize, isize, u128);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+/// A single day of the advent calendar.
+pub trait Solver {
+    fn day(&self) -> usize;
+
+    fn title(&self) -> &'static str;
+
+    /// Parse the input without solving anything, so that parsing can be benchmarked on its own.
+    /// The parts parse the input themselves.
+    fn parse(&self, input: &str) -> Result<(), ParseError>;
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError>;
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError>;
+
+    /// Write a visualization of the puzzle, for days that have one.
+    fn show(&self, _input: &str, _options: &ShowOptions, _out: &mut dyn Write) -> Result<(), ShowError> {
+        Err(ShowError::Unsupported)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> usize {
+        match self {
+            Part::One => 1,
+            Part::Two => 2
+        }
+    }
+}
+
+/// Solve one part of the given day. Parse errors are located in the input and tagged with the day.
+pub fn solve(solver: &dyn Solver, part: Part, input: &str) -> Result<Answer, ParseError> {
+    let answer = match part {
+        Part::One => solver.part1(input),
+        Part::Two => solver.part2(input)
+    };
+
+    answer.map_err(|e| e.locate(input).in_day(solver.day()))
+}
+
+/// Visualize the given day. Parse errors are located like in [solve].
+pub fn show(solver: &dyn Solver, input: &str, options: &ShowOptions, out: &mut dyn Write) -> Result<(), ShowError> {
+    solver.show(input, options, out).map_err(|e| match e {
+        ShowError::Parse(e) => ShowError::Parse(e.locate(input).in_day(solver.day())),
+        e => e
+    })
+}
+
+/// All solved days, ordered by day.
+pub const SOLVERS: [&dyn Solver; 17] = [
+    &p1::P1,
+    &p2::P2,
+    &p3::P3,
+    &p4::P4,
+    &p5::P5,
+    &p6::P6,
+    &p7::P7,
+    &p8::P8,
+    &p9::P9,
+    &p10::P10,
+    &p11::P11,
+    &p12::P12,
+    &p13::P13,
+    &p14::P14,
+    &p15::P15,
+    &p16::P16,
+    &p17::P17,
+];
+
+pub fn solver_for_day(day: usize) -> Option<&'static dyn Solver> {
+    SOLVERS.into_iter().find(|solver| solver.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solver::{Answer, SOLVERS};
+
+    #[test]
+    fn solvers_are_ordered_by_day() {
+        SOLVERS
+            .into_iter()
+            .enumerate()
+            .for_each(|(i, solver)| assert_eq!(solver.day(), i + 1))
+    }
+
+    #[test]
+    fn answer_to_json_works() {
+        assert_eq!(Answer::from(42usize).to_json(), "42");
+        assert_eq!(Answer::from("CMZ\"\\".to_string()).to_json(), "\"CMZ\\\"\\\\\"");
+        assert_eq!(Answer::Bitmap(vec![vec![true, false], vec![false, true]]).to_json(), "[\"#.\",\".#\"]");
+
+        let unreadable = Answer::Unreadable { bitmap: vec![vec![true, false]], reason: "unknown glyphs".to_string() };
+        assert_eq!((unreadable.to_string(), unreadable.to_json()), ("#.".to_string(), "[\"#.\"]".to_string()));
+    }
+}
\ No newline at end of file